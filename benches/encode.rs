@@ -0,0 +1,48 @@
+//! Encoder timing, reported as modules per second.
+//!
+//! cargo bench --no-default-features --features bench
+
+use std::time::Instant;
+
+use barcode::barcode_encode::{self, BarcodeFormat};
+
+const ITERATIONS: u32 = 20_000;
+
+const CASES: &[(&str, &str, BarcodeFormat)] = &[
+    ("code128 text", "Hello, World!", BarcodeFormat::Code128),
+    ("code128 digits", "123456789012345678901234", BarcodeFormat::Code128),
+    ("code128 mixed", "LOT-2024-000123-A/77", BarcodeFormat::Code128),
+    ("code128 max", "The quick brown fox jumps over the lazy dog 0123456789 ABCDEFGHIJKLMNOPQRSTUVWXY", BarcodeFormat::Code128),
+    ("code39", "PRECURSOR-123", BarcodeFormat::Code39),
+    ("ean13", "590123412345", BarcodeFormat::Ean13),
+    ("upca", "03600029145", BarcodeFormat::UpcA),
+    ("telepen", "Hello, World!", BarcodeFormat::Telepen),
+    ("std2of5", "1234567", BarcodeFormat::Std2of5),
+    ("matrix2of5", "1234567", BarcodeFormat::Matrix2of5),
+    ("code32", "01234567", BarcodeFormat::Code32),
+    ("pzn", "0123456", BarcodeFormat::Pzn),
+];
+
+fn main() {
+    println!("{:<16} {:>8} {:>12} {:>14}", "case", "modules", "ns/encode", "modules/s");
+    for &(name, text, format) in CASES {
+        let modules = match barcode_encode::encode(text, format) {
+            Ok(barcode) => barcode.modules.len(),
+            Err(e) => {
+                println!("{:<16} failed: {}", name, e);
+                continue;
+            }
+        };
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let barcode = barcode_encode::encode(std::hint::black_box(text), format);
+            std::hint::black_box(barcode).ok();
+        }
+        let elapsed = start.elapsed();
+
+        let ns = elapsed.as_nanos() as f64 / ITERATIONS as f64;
+        let rate = modules as f64 * ITERATIONS as f64 / elapsed.as_secs_f64();
+        println!("{:<16} {:>8} {:>12.0} {:>14.0}", name, modules, ns, rate);
+    }
+}
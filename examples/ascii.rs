@@ -0,0 +1,33 @@
+//! Print a barcode as `#`/space rows, to check encoders without a Precursor.
+//!
+//! cargo run --example ascii --no-default-features -- "HELLO-123" [c128|c39|ean13|upca|telepen|std25|mat25|c32|pzn]
+
+use barcode::barcode_encode::{self, BarcodeFormat};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let text = args.next().unwrap_or_else(|| String::from("PRECURSOR"));
+    let format = match args.next().as_deref() {
+        Some("c128") => BarcodeFormat::Code128,
+        Some("c39") => BarcodeFormat::Code39,
+        Some("ean13") => BarcodeFormat::Ean13,
+        Some("upca") => BarcodeFormat::UpcA,
+        Some("telepen") => BarcodeFormat::Telepen,
+        Some("std25") => BarcodeFormat::Std2of5,
+        Some("mat25") => BarcodeFormat::Matrix2of5,
+        Some("c32") => BarcodeFormat::Code32,
+        Some("pzn") => BarcodeFormat::Pzn,
+        _ => barcode_encode::auto_detect(&text),
+    };
+
+    match barcode_encode::encode(&text, format) {
+        Ok(barcode) => {
+            print!("{}", barcode.to_ascii(1));
+            println!("{}, {} modules", format.label(), barcode.modules.len());
+        }
+        Err(e) => {
+            eprintln!("{}: {}", format.label(), e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -2,10 +2,15 @@
 
 extern crate alloc;
 use alloc::string::String;
+use alloc::vec;
 use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
 
-use crate::barcode_encode::{self, Barcode, BarcodeFormat};
-use crate::storage::Storage;
+use crate::barcode_encode::{self, Barcode, BarcodeFormat, CheckMode, EncodeOptions};
+use crate::export::{self, ExportRow};
+use crate::power::Power;
+use crate::storage::{self, Storage, StorageError};
+use crate::strings::{self, Language, Str};
 
 // Standard key codes (ecosystem standard)
 const KEY_UP: char = '\u{2191}';
@@ -14,8 +19,63 @@ const KEY_LEFT: char = '\u{2190}';
 const KEY_RIGHT: char = '\u{2192}';
 const KEY_ENTER: char = '\r';
 const KEY_BACKSPACE: char = '\u{0008}';
+/// D-pad center (select) key.
+const KEY_CENTER: char = '\u{2234}';
 
 const MAX_TEXT_LEN: usize = 80;
+const MAX_NAME_LEN: usize = 30;
+const MAX_AFFIX_LEN: usize = 16;
+/// Undo steps kept; older ones are dropped.
+const UNDO_DEPTH: usize = 8;
+
+/// Most time between two presses of the same key, in ms, for them to count
+/// as one held key. Separate taps come further apart.
+const REPEAT_GAP_MS: u64 = 200;
+
+/// Code 128 has no length limit of its own; long payloads are valid, just wide.
+const MAX_CODE128_LEN: usize = 160;
+
+/// Side margin, in pixels, either side of a symbol on the Display screen.
+const DISPLAY_MARGIN: usize = 4;
+
+/// Canvas width assumed until `ui::draw` reports the real one: Precursor's
+/// 336px screen.
+const DEFAULT_CANVAS_WIDTH: isize = 336;
+
+/// Separates two payloads shown stacked on one screen, e.g. an EAN and a
+/// Code 128 lot number. The second one always gets its format auto-detected.
+pub const STACK_DELIMITER: &str = "||";
+
+/// Starting state for the random test data generator.
+#[cfg(feature = "debug-overlay")]
+const RANDOM_SEED: u32 = 0x2545_F491;
+
+/// A key on the Keypad screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeypadKey {
+    Digit(char),
+    Delete,
+    Done,
+}
+
+/// Keypad grid, row by row, laid out like a phone.
+pub const KEYPAD_KEYS: [KeypadKey; 12] = [
+    KeypadKey::Digit('1'), KeypadKey::Digit('2'), KeypadKey::Digit('3'),
+    KeypadKey::Digit('4'), KeypadKey::Digit('5'), KeypadKey::Digit('6'),
+    KeypadKey::Digit('7'), KeypadKey::Digit('8'), KeypadKey::Digit('9'),
+    KeypadKey::Delete, KeypadKey::Digit('0'), KeypadKey::Done,
+];
+pub const KEYPAD_COLUMNS: usize = 3;
+
+/// Category given to auto-saved codes, so they can be filtered and purged together.
+const AUTOSAVE_CATEGORY: &str = "auto";
+
+/// Known-good samples shown by the Self Test menu entry, for checking a scanner.
+pub const SELF_TEST_SAMPLES: &[(&str, BarcodeFormat)] = &[
+    ("SELFTEST123", BarcodeFormat::Code128),
+    ("5901234123457", BarcodeFormat::Ean13),
+    ("CODE39-TEST", BarcodeFormat::Code39),
+];
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppState {
@@ -24,45 +84,348 @@ pub enum AppState {
     Display,
     SavePrompt,
     SaveNameEntry,
+    /// The code to save already is in the list under another name.
+    ConfirmDuplicate,
+    /// Q on Input with text the format can't take a 'q' into.
+    ConfirmDiscard,
     LoadList,
+    CategoryEntry,
+    AffixEntry,
     Settings,
     Help,
+    FormatInfo,
+    ComposeGtin,
+    /// On-screen digit grid over Input, for numeric formats.
+    Keypad,
+}
+
+/// Screen area that needs repainting after a key press. Anything other than
+/// `Full` lets `ui::draw` skip the full-screen clear.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RedrawRegion {
+    Full,
+    /// Input box and status line on the Input screen.
+    InputText,
+    /// The two main menu rows whose highlight moved.
+    MenuRows(usize, usize),
+    /// The two settings rows whose highlight moved (or one row, twice, on a value change).
+    SettingsRows(usize, usize),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MenuItem {
     NewBarcode,
+    ComposeGtin,
+    PriceCode,
     SavedCodes,
+    SelfTest,
     Settings,
     Help,
 }
 
 impl MenuItem {
-    pub fn label(&self) -> &'static str {
+    pub fn label(&self) -> Str {
         match self {
-            MenuItem::NewBarcode => "New Barcode",
-            MenuItem::SavedCodes => "Saved Codes",
-            MenuItem::Settings => "Settings",
-            MenuItem::Help => "Help",
+            MenuItem::NewBarcode => Str::MenuNewBarcode,
+            MenuItem::ComposeGtin => Str::MenuComposeGtin,
+            MenuItem::PriceCode => Str::MenuPriceCode,
+            MenuItem::SavedCodes => Str::MenuSavedCodes,
+            MenuItem::SelfTest => Str::MenuSelfTest,
+            MenuItem::Settings => Str::MenuSettings,
+            MenuItem::Help => Str::MenuHelp,
         }
     }
 
     pub fn all() -> &'static [MenuItem] {
         &[
             MenuItem::NewBarcode,
+            MenuItem::ComposeGtin,
+            MenuItem::PriceCode,
             MenuItem::SavedCodes,
+            MenuItem::SelfTest,
             MenuItem::Settings,
             MenuItem::Help,
         ]
     }
 }
 
+/// Screen the app opens on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StartScreen {
+    Menu,
+    NewBarcode,
+    Saved,
+}
+
+impl StartScreen {
+    pub fn label(&self) -> Str {
+        match self {
+            StartScreen::Menu => Str::StartMenu,
+            StartScreen::NewBarcode => Str::MenuNewBarcode,
+            StartScreen::Saved => Str::MenuSavedCodes,
+        }
+    }
+
+    /// Short code used in the settings JSON.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StartScreen::Menu => "menu",
+            StartScreen::NewBarcode => "new",
+            StartScreen::Saved => "saved",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<StartScreen> {
+        match code {
+            "menu" => Some(StartScreen::Menu),
+            "new" => Some(StartScreen::NewBarcode),
+            "saved" => Some(StartScreen::Saved),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> StartScreen {
+        match self {
+            StartScreen::Menu => StartScreen::NewBarcode,
+            StartScreen::NewBarcode => StartScreen::Saved,
+            StartScreen::Saved => StartScreen::Menu,
+        }
+    }
+}
+
+/// Size of the human-readable line under the barcode on the Display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextSize {
+    Small,
+    Normal,
+    Large,
+}
+
+impl TextSize {
+    pub fn label(&self) -> Str {
+        match self {
+            TextSize::Small => Str::TextSmall,
+            TextSize::Normal => Str::TextNormal,
+            TextSize::Large => Str::TextLarge,
+        }
+    }
+
+    /// Short code used in the settings JSON.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TextSize::Small => "small",
+            TextSize::Normal => "normal",
+            TextSize::Large => "large",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<TextSize> {
+        match code {
+            "small" => Some(TextSize::Small),
+            "normal" => Some(TextSize::Normal),
+            "large" => Some(TextSize::Large),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> TextSize {
+        match self {
+            TextSize::Small => TextSize::Normal,
+            TextSize::Normal => TextSize::Large,
+            TextSize::Large => TextSize::Small,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingItem {
+    Format,
+    AutoDetect,
+    BarWidth,
+    BarHeight,
+    AutoHeight,
+    TextSize,
+    TrimInput,
+    StripNonDigits,
+    MaxBrightness,
+    ScanTestTime,
+    Code39Case,
+    Std2of5Check,
+    ShowCheckChar,
+    EanZeroPad,
+    UpcText,
+    WideRatio,
+    Code39Gap,
+    CheckDigitMode,
+    AppendEnter,
+    Code128ForceB,
+    Code128Latin1,
+    ResumeLast,
+    AutoSave,
+    PrivateMode,
+    StartScreen,
+    Mirror,
+    CenterBars,
+    DrawFrame,
+    TileCount,
+    KeyRepeat,
+    Prefix,
+    Suffix,
+    Language,
+}
+
+impl SettingItem {
+    pub fn label(&self) -> Str {
+        match self {
+            SettingItem::Format => Str::SettingFormat,
+            SettingItem::AutoDetect => Str::SettingAutoDetect,
+            SettingItem::BarWidth => Str::SettingBarWidth,
+            SettingItem::BarHeight => Str::SettingBarHeight,
+            SettingItem::AutoHeight => Str::SettingAutoHeight,
+            SettingItem::TextSize => Str::SettingTextSize,
+            SettingItem::TrimInput => Str::SettingTrimInput,
+            SettingItem::StripNonDigits => Str::SettingStripNonDigits,
+            SettingItem::MaxBrightness => Str::SettingMaxBrightness,
+            SettingItem::ScanTestTime => Str::SettingScanTestTime,
+            SettingItem::Code39Case => Str::SettingCode39Case,
+            SettingItem::Std2of5Check => Str::SettingStd2of5Check,
+            SettingItem::ShowCheckChar => Str::SettingShowCheckChar,
+            SettingItem::EanZeroPad => Str::SettingEanZeroPad,
+            SettingItem::UpcText => Str::SettingUpcText,
+            SettingItem::WideRatio => Str::SettingWideRatio,
+            SettingItem::Code39Gap => Str::SettingCode39Gap,
+            SettingItem::CheckDigitMode => Str::SettingCheckMode,
+            SettingItem::AppendEnter => Str::SettingAppendEnter,
+            SettingItem::Code128ForceB => Str::SettingCode128ForceB,
+            SettingItem::Code128Latin1 => Str::SettingCode128Latin1,
+            SettingItem::ResumeLast => Str::SettingResumeLast,
+            SettingItem::AutoSave => Str::SettingAutoSave,
+            SettingItem::PrivateMode => Str::SettingPrivateMode,
+            SettingItem::StartScreen => Str::SettingStartScreen,
+            SettingItem::Mirror => Str::SettingMirror,
+            SettingItem::CenterBars => Str::SettingCenterBars,
+            SettingItem::DrawFrame => Str::SettingDrawFrame,
+            SettingItem::TileCount => Str::SettingTileCount,
+            SettingItem::KeyRepeat => Str::SettingKeyRepeat,
+            SettingItem::Prefix => Str::SettingPrefix,
+            SettingItem::Suffix => Str::SettingSuffix,
+            SettingItem::Language => Str::SettingLanguage,
+        }
+    }
+
+    pub fn all() -> &'static [SettingItem] {
+        &[
+            SettingItem::Format,
+            SettingItem::AutoDetect,
+            SettingItem::BarWidth,
+            SettingItem::BarHeight,
+            SettingItem::AutoHeight,
+            SettingItem::TextSize,
+            SettingItem::TrimInput,
+            SettingItem::StripNonDigits,
+            SettingItem::MaxBrightness,
+            SettingItem::ScanTestTime,
+            SettingItem::Code39Case,
+            SettingItem::Std2of5Check,
+            SettingItem::ShowCheckChar,
+            SettingItem::EanZeroPad,
+            SettingItem::UpcText,
+            SettingItem::WideRatio,
+            SettingItem::Code39Gap,
+            SettingItem::CheckDigitMode,
+            SettingItem::AppendEnter,
+            SettingItem::Code128ForceB,
+            SettingItem::Code128Latin1,
+            SettingItem::ResumeLast,
+            SettingItem::AutoSave,
+            SettingItem::PrivateMode,
+            SettingItem::StartScreen,
+            SettingItem::Mirror,
+            SettingItem::CenterBars,
+            SettingItem::DrawFrame,
+            SettingItem::TileCount,
+            SettingItem::KeyRepeat,
+            SettingItem::Prefix,
+            SettingItem::Suffix,
+            SettingItem::Language,
+        ]
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BarcodeSettings {
     pub bar_width: u8,   // 1-4 px per module
     pub bar_height: u16, // 80-300 px
+    /// Fill the Display's height with the bars, ignoring `bar_height`.
+    pub auto_height: bool,
+    pub text_size: TextSize,
     pub format: BarcodeFormat,
     pub auto_format: bool,
+    pub trim_input: bool, // strip leading/trailing whitespace before encoding
+    /// For digit-only formats, drop spaces, dashes and any other non-digits
+    /// (as in a GTIN copied off a label) when generating.
+    pub strip_nondigits: bool,
+    pub max_brightness_on_display: bool,
+    /// Seconds the Display's scan test holds full brightness and height, 5-60.
+    pub scan_test_secs: u8,
+    /// Keep lowercase in Code 39 instead of folding it to uppercase. Plain
+    /// Code 39 has no lowercase; it takes Extended Code 39, which this app
+    /// doesn't encode, so with this on lowercase input is an error.
+    pub code39_preserve_case: bool,
+    /// Append the optional mod-10 check digit to Standard 2 of 5.
+    pub std2of5_check: bool,
+    /// Include an optional check character the app appended in the
+    /// human-readable text. Printed labels usually leave it out.
+    pub show_check_char: bool,
+    /// Left-pad EAN-13 input shorter than 12 digits with zeros instead of
+    /// rejecting it.
+    pub ean_zero_pad: bool,
+    /// Print a 0-leading EAN-13 as its 12-digit UPC-A under the bars, still
+    /// labelled EAN-13. The U key on the Display does both.
+    pub upc_text: bool,
+    /// Wide element width in Code 39 and 2 of 5, as a multiple of narrow: 2 or 3.
+    /// Some readers only accept one.
+    pub wide_ratio: u8,
+    /// Code 39 gap between characters, in narrow widths: 1-3.
+    pub code39_gap: u8,
+    /// What a typed EAN/UPC check digit does: replaced, required to match, or kept.
+    pub check_digit_mode: CheckMode,
+    /// Code 128 only: end the symbol with a CR (via subset A) so keyboard-
+    /// wedge scanners press Enter after the data.
+    pub append_enter: bool,
+    /// Code 128 only: keep digits in subset B instead of packing pairs into
+    /// subset C, for readers that only take subset B.
+    pub code128_force_b: bool,
+    /// Code 128 only: accept Latin-1 characters 128-255 (e.g. ä, é),
+    /// encoded with FNC4. Not every reader decodes them.
+    pub code128_latin1: bool,
+    /// Reopen where the app was last closed: the barcode that was showing,
+    /// the Input screen with its text, or the saved list at its selection.
+    pub resume_last: bool,
+    /// Save every newly generated code under a numbered name in the
+    /// `AUTOSAVE_CATEGORY` category, skipping text/format pairs already saved.
+    pub autosave: bool,
+    /// For sensitive payloads: generated codes aren't auto-saved, kept for
+    /// Resume Last, or offered again from the main menu.
+    pub private_mode: bool,
+    /// Screen to open on when there is nothing to resume.
+    pub start_screen: StartScreen,
+    /// Draw the bars right-to-left, for reflective or transfer printing.
+    pub mirror: bool,
+    /// Center the Display on the bars alone rather than the whole symbol
+    /// with its quiet zones, for symbols whose two quiet zones differ.
+    pub center_bars: bool,
+    /// Outline the symbol, quiet zones included, as a cutting guide.
+    pub draw_frame: bool,
+    pub tile_count: u8, // 2-12 copies in the print layout
+    /// Held Up/Down in lists and Backspace in text fields speed up the
+    /// longer the same key keeps arriving.
+    pub key_repeat: bool,
+    /// Put before and after every typed input when generating, e.g. a site
+    /// code. Both part of what gets encoded and saved.
+    pub prefix: String,
+    pub suffix: String,
+    pub language: Language,
 }
 
 impl Default for BarcodeSettings {
@@ -70,8 +433,37 @@ impl Default for BarcodeSettings {
         Self {
             bar_width: 2,
             bar_height: 200,
+            auto_height: false,
+            text_size: TextSize::Normal,
             format: BarcodeFormat::Code128,
             auto_format: true,
+            trim_input: true,
+            strip_nondigits: false,
+            max_brightness_on_display: true,
+            scan_test_secs: 10,
+            code39_preserve_case: false,
+            std2of5_check: false,
+            show_check_char: false,
+            ean_zero_pad: false,
+            upc_text: false,
+            wide_ratio: barcode_encode::DEFAULT_WIDE_RATIO,
+            code39_gap: barcode_encode::DEFAULT_CODE39_GAP,
+            check_digit_mode: CheckMode::Recompute,
+            append_enter: false,
+            code128_force_b: false,
+            code128_latin1: false,
+            resume_last: false,
+            autosave: false,
+            private_mode: false,
+            start_screen: StartScreen::Menu,
+            mirror: false,
+            center_bars: false,
+            draw_frame: false,
+            tile_count: 4,
+            key_repeat: false,
+            prefix: String::new(),
+            suffix: String::new(),
+            language: Language::En,
         }
     }
 }
@@ -81,6 +473,88 @@ pub struct SavedBarcode {
     pub name: String,
     pub text: String,
     pub format: BarcodeFormat,
+    /// User-defined group; `None` is listed as "(none)".
+    pub category: Option<String>,
+    /// Seconds since the Unix epoch when the code was saved, 0 for codes
+    /// saved before this was recorded or while the clock wasn't set.
+    pub created: u64,
+}
+
+/// Where the user was when the app last went to the background, restored
+/// on the next start with Resume Last on.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Screen to come back to when no barcode was shown.
+    pub screen: StartScreen,
+    pub input_text: String,
+    pub load_index: usize,
+    /// Barcode on the Display, with any prefix and suffix already in it.
+    pub shown: Option<(String, BarcodeFormat)>,
+}
+
+/// State captured before a destructive key, restored by undo.
+#[derive(Debug, Clone)]
+pub enum UndoEntry {
+    /// `input_text` before a Backspace.
+    Input(String),
+    /// Saved codes a delete or purge removed, with the index each had.
+    RemovedCodes(Vec<(usize, SavedBarcode)>),
+    /// Names of the saved codes an import added.
+    ImportedCodes(Vec<String>),
+}
+
+/// Which saved codes the list shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CategoryFilter {
+    All,
+    /// Codes in one category, or uncategorized ones for `Only(None)`.
+    Only(Option<String>),
+}
+
+/// Bar rectangles for the barcode on the Display screen, reused across redraws
+/// until the text, format, bar size, or canvas size changes.
+#[derive(Debug, Clone)]
+pub struct RenderCache {
+    pub text: String,
+    pub format: BarcodeFormat,
+    pub bar_width: u8,
+    pub bar_height: u16,
+    /// Bars fill the content height: Auto Height, or a scan test running.
+    pub full_height: bool,
+    pub mirror: bool,
+    pub center_bars: bool,
+    /// Text size moves the bars up or down to make room for the text line.
+    pub text_size: TextSize,
+    /// Canvas width and height the rectangles were laid out for.
+    pub screen: (isize, isize),
+    /// Pixel rectangles (x0, y0, x1, y1), one per run of dark modules.
+    pub rects: Vec<(isize, isize, isize, isize)>,
+}
+
+impl RenderCache {
+    pub fn matches(&self, barcode: &Barcode, settings: &BarcodeSettings, full_height: bool, screen: (isize, isize)) -> bool {
+        self.text == barcode.text
+            && self.format == barcode.format
+            && self.bar_width == settings.bar_width
+            && self.bar_height == settings.bar_height
+            && self.full_height == full_height
+            && self.mirror == settings.mirror
+            && self.center_bars == settings.center_bars
+            && self.text_size == settings.text_size
+            && self.screen == screen
+    }
+}
+
+/// Width in pixels of the barcode thumbnails in the saved list.
+pub const THUMB_WIDTH: usize = 16;
+
+/// A saved code squeezed into `THUMB_WIDTH` columns for the saved list.
+/// `columns` is `None` when the code doesn't encode.
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    pub text: String,
+    pub format: BarcodeFormat,
+    pub columns: Option<[bool; THUMB_WIDTH]>,
 }
 
 pub struct BarcodeApp {
@@ -88,14 +562,178 @@ pub struct BarcodeApp {
     pub menu_index: usize,
     pub input_text: String,
     pub barcode: Option<Barcode>,
+    /// Second code drawn below `barcode` when the input held `STACK_DELIMITER`.
+    pub stacked: Option<Barcode>,
     pub barcode_text: String,
     pub settings: BarcodeSettings,
+    /// Full records, hydrated from PDDB on demand by `ensure_codes_loaded`.
     pub saved_codes: Vec<SavedBarcode>,
+    /// Names from the PDDB index, loaded at startup for the main menu count.
+    pub saved_names: Vec<String>,
+    /// Set when `saved_codes` may not match PDDB and must be reloaded before use.
+    codes_stale: bool,
+    /// Position in `visible_codes()`, not in `saved_codes`.
     pub load_index: usize,
+    pub category_filter: CategoryFilter,
+    pub category_input: String,
+    /// Prefix or suffix being edited, and which of the two.
+    pub affix_input: String,
+    pub affix_is_suffix: bool,
+    /// Index into `SELF_TEST_SAMPLES` while the Display shows a self-test code.
+    pub self_test_index: Option<usize>,
+    /// Display shows the saved code at `load_index`, so Left/Right step
+    /// through the saved list.
+    pub from_saved: bool,
+    /// Display shows the print layout grid instead of one barcode.
+    pub tile_mode: bool,
+    /// Display shows the module runs instead of the bars. Only reachable
+    /// with the `debug-overlay` feature.
+    pub show_bits: bool,
+    /// Display draws a tick every `RULER_STEP` modules above the bars. Only
+    /// reachable with the `debug-overlay` feature.
+    pub show_ruler: bool,
+    /// Display labels a 0-leading EAN-13 as its UPC-A. Presentation only:
+    /// the bars and the saved format stay EAN-13.
+    pub upc_view: bool,
+    /// Input screen preview from the Center key, with the payload it was
+    /// encoded from. Hidden again once the input or format changes.
+    inline_preview: Option<(String, Barcode)>,
+    /// xorshift32 state for the random test data key. Fixed at startup so a
+    /// session's sequence of payloads can be reproduced.
+    #[cfg(feature = "debug-overlay")]
+    random_seed: u32,
+    /// Format the current bar size was picked for by `apply_bar_size`.
+    sized_format: Option<BarcodeFormat>,
+    /// Bar sizes the user chose for a format this session; these win over
+    /// `BarcodeFormat::default_bar_size`.
+    bar_size_overrides: Vec<(BarcodeFormat, u8, u16)>,
     pub save_name: String,
+    /// Name the code being saved is already saved under, while asking
+    /// whether to save it again.
+    pub duplicate_name: String,
+    /// Compose GTIN fields. The item reference is being typed once
+    /// `compose_on_item` is set. With `compose_price` the two fields are
+    /// the item number and price of a variable-weight UPC-A instead.
+    pub compose_prefix: String,
+    pub compose_item: String,
+    pub compose_on_item: bool,
+    pub compose_price: bool,
+    /// Selected key on the Keypad screen, an index into `KEYPAD_KEYS`.
+    pub keypad_index: usize,
+    /// Format described on the Format Info screen.
+    pub info_format: BarcodeFormat,
+    /// Screen Format Info goes back to.
+    info_return: AppState,
     pub settings_index: usize,
+    /// First help line shown; the help text is taller than the screen.
+    pub help_scroll: usize,
     pub needs_redraw: bool,
+    /// Why the last Enter on the Input screen didn't produce a barcode.
+    pub last_error: Option<String>,
+    /// One-shot message shown on the next Display redraw, cleared by the following key.
+    pub status_message: Option<String>,
+    pub redraw_region: RedrawRegion,
+    /// Last key, when it arrived, and how many times in a row it has
+    /// arrived within `REPEAT_GAP_MS` of the one before, for `repeat_step`.
+    repeat_key: char,
+    repeat_at: u64,
+    repeat_count: u32,
+    /// Key timing for `repeat_step`; without it there's no acceleration.
+    ticktimer: Option<ticktimer_server::Ticktimer>,
+    undo: Vec<UndoEntry>,
+    /// Filled lazily by `ui::draw_display`, hence the `RefCell`.
+    pub render_cache: RefCell<Option<RenderCache>>,
+    /// Saved-list thumbnails by `saved_codes` index, filled lazily by
+    /// `ui::draw_load_list` and cleared whenever the list is written or reloaded.
+    pub thumbnails: RefCell<Vec<Option<Thumbnail>>>,
+    /// Width of the canvas last drawn, set by `ui::draw`.
+    pub canvas_width: Cell<isize>,
     storage: Option<Storage>,
+    power: Option<Power>,
+    foreground: bool,
+    /// Seconds left in the Display's scan test, which holds the code at full
+    /// brightness and height. Counted down by `tick`.
+    pub scan_test: Option<u8>,
+    /// Bumped on every scan test start, so ticks from a cancelled one are ignored.
+    scan_test_run: usize,
+    /// Scan test `main` should start a one-second ticker for: run and seconds.
+    ticker_request: Option<(usize, u8)>,
+}
+
+/// Printable Latin-1 above ASCII: no-break space to ÿ.
+fn is_latin1_graphic(c: char) -> bool {
+    ('\u{A0}'..='\u{FF}').contains(&c)
+}
+
+/// Trim a saved name and collapse runs of spaces, so names that look the
+/// same in the list are the same PDDB key.
+fn normalize_name(name: &str) -> String {
+    let mut out = String::new();
+    for word in name.split(' ').filter(|w| !w.is_empty()) {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+/// Parse one `name,gtin` (or tab-separated) import line. The GTIN must be a
+/// full EAN-13 or UPC-A with the right check digit. Lines of three or more
+/// tab-separated fields are export rows instead.
+fn parse_import_line(line: &str) -> Result<SavedBarcode, String> {
+    if line.split('\t').count() >= 3 {
+        return parse_export_row(line);
+    }
+    let (name, gtin) = line
+        .split_once([',', '\t'])
+        .ok_or_else(|| String::from("expected name,gtin"))?;
+    let name = normalize_name(name);
+    if name.is_empty() || name.len() > MAX_NAME_LEN || !name.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+        return Err(alloc::format!("bad name {:?}", name));
+    }
+    let gtin = gtin.trim();
+    if let Some(c) = gtin.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(alloc::format!("{}", barcode_encode::EncodeError::InvalidChar(c)));
+    }
+    let format = match gtin.len() {
+        13 => BarcodeFormat::Ean13,
+        12 => BarcodeFormat::UpcA,
+        got => return Err(alloc::format!("{}", barcode_encode::EncodeError::WrongLength { got, min: 12, max: 13 })),
+    };
+    barcode_encode::verify_check_digit(gtin, format).map_err(|e| alloc::format!("{}", e))?;
+    Ok(SavedBarcode { name, text: String::from(gtin), format, category: None, created: now_secs() })
+}
+
+/// One saved code as an export row, with `format` as stored in PDDB and an
+/// empty category for none.
+fn export_row(code: &SavedBarcode) -> String {
+    export::export_row(&ExportRow {
+        name: code.name.clone(),
+        text: code.text.clone(),
+        format: String::from(storage::format_key(code.format)),
+        created: code.created,
+        category: code.category.clone().unwrap_or_default(),
+    })
+}
+
+/// Parse a row written by `export_row`, checking the name and format.
+fn parse_export_row(line: &str) -> Result<SavedBarcode, String> {
+    let row = export::parse_export_row(line)?;
+    let name = normalize_name(&row.name);
+    if name.is_empty() || name.len() > MAX_NAME_LEN || !name.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+        return Err(alloc::format!("bad name {:?}", name));
+    }
+    let format = storage::format_by_key(&row.format).ok_or_else(|| alloc::format!("unknown format {:?}", row.format))?;
+    let category = Some(row.category).filter(|c| !c.is_empty());
+    Ok(SavedBarcode { name, text: row.text, format, category, created: row.created })
+}
+
+/// Seconds since the Unix epoch, or 0 when the clock isn't set.
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 impl BarcodeApp {
@@ -105,14 +743,57 @@ impl BarcodeApp {
             menu_index: 0,
             input_text: String::new(),
             barcode: None,
+            stacked: None,
             barcode_text: String::new(),
             settings: BarcodeSettings::default(),
             saved_codes: Vec::new(),
+            saved_names: Vec::new(),
+            codes_stale: false,
             load_index: 0,
+            category_filter: CategoryFilter::All,
+            category_input: String::new(),
+            affix_input: String::new(),
+            affix_is_suffix: false,
+            self_test_index: None,
+            from_saved: false,
+            tile_mode: false,
+            show_bits: false,
+            show_ruler: false,
+            upc_view: false,
+            inline_preview: None,
+            #[cfg(feature = "debug-overlay")]
+            random_seed: RANDOM_SEED,
+            sized_format: None,
+            bar_size_overrides: Vec::new(),
+            info_format: BarcodeFormat::Code128,
+            info_return: AppState::MainMenu,
             save_name: String::new(),
+            duplicate_name: String::new(),
+            compose_prefix: String::new(),
+            compose_item: String::new(),
+            compose_on_item: false,
+            compose_price: false,
+            keypad_index: 0,
             settings_index: 0,
+            help_scroll: 0,
             needs_redraw: true,
+            last_error: None,
+            status_message: None,
+            redraw_region: RedrawRegion::Full,
+            repeat_key: '\0',
+            repeat_at: 0,
+            repeat_count: 0,
+            ticktimer: ticktimer_server::Ticktimer::new().ok(),
+            undo: Vec::new(),
+            render_cache: RefCell::new(None),
+            thumbnails: RefCell::new(Vec::new()),
+            canvas_width: Cell::new(DEFAULT_CANVAS_WIDTH),
             storage: None,
+            power: None,
+            foreground: true,
+            scan_test: None,
+            scan_test_run: 0,
+            ticker_request: None,
         }
     }
 
@@ -122,78 +803,562 @@ impl BarcodeApp {
                 if let Some(settings) = s.load_settings() {
                     self.settings = settings;
                 }
-                self.saved_codes = s.load_codes();
+                self.saved_names = s.load_index();
+                self.codes_stale = true;
+                let last = if self.settings.resume_last { s.load_last() } else { None };
                 self.storage = Some(s);
+                if let Some(last) = last {
+                    self.restore(last);
+                }
+                if self.state == AppState::MainMenu {
+                    match self.settings.start_screen {
+                        StartScreen::Menu => {}
+                        StartScreen::NewBarcode => self.state = AppState::Input,
+                        StartScreen::Saved => {
+                            self.ensure_codes_loaded();
+                            self.state = AppState::LoadList;
+                        }
+                    }
+                }
             }
             Err(e) => log::warn!("Failed to init storage: {:?}", e),
         }
     }
 
+    /// Go back to the screen in `last`, or stay on the menu if that is no
+    /// longer possible.
+    fn restore(&mut self, last: Snapshot) {
+        if let Some((text, format)) = last.shown {
+            self.input_text = text;
+            // Saved with any prefix and suffix already in it
+            self.encode_as(String::from(self.effective_input()), format);
+            if self.state != AppState::Display {
+                // Settings changed since; stay on the menu
+                log::warn!("Last barcode no longer encodes: {:?}", self.last_error.take());
+                self.input_text.clear();
+            }
+            return;
+        }
+        match last.screen {
+            StartScreen::Menu => {}
+            StartScreen::NewBarcode => {
+                self.input_text = last.input_text;
+                self.state = AppState::Input;
+            }
+            StartScreen::Saved => {
+                self.ensure_codes_loaded();
+                // Codes may have been deleted or imported since
+                self.load_index = last.load_index.min(self.visible_codes().len().saturating_sub(1));
+                self.state = AppState::LoadList;
+            }
+        }
+    }
+
+    pub fn attach_power(&mut self, power: Power) {
+        self.power = Some(power);
+        self.sync_keep_awake();
+    }
+
+    pub fn set_foreground(&mut self, foreground: bool) {
+        self.foreground = foreground;
+        self.sync_keep_awake();
+    }
+
+    /// Hold the shown code at full brightness and height for
+    /// `scan_test_secs`, with `main` ticking it down.
+    fn start_scan_test(&mut self) {
+        let secs = self.settings.scan_test_secs;
+        self.scan_test_run = self.scan_test_run.wrapping_add(1);
+        self.scan_test = Some(secs);
+        self.ticker_request = Some((self.scan_test_run, secs));
+    }
+
+    /// Whether the Display bars fill the content height instead of `bar_height`.
+    pub fn full_height(&self) -> bool {
+        self.settings.auto_height || self.scan_test.is_some()
+    }
+
+    /// Scan test `main` should start ticking, as (run, seconds).
+    pub fn take_ticker_request(&mut self) -> Option<(usize, u8)> {
+        self.ticker_request.take()
+    }
+
+    /// One second of scan test `run` has passed.
+    pub fn tick(&mut self, run: usize) {
+        if run != self.scan_test_run {
+            return;
+        }
+        if let Some(left) = self.scan_test {
+            self.scan_test = left.checked_sub(1).filter(|&n| n > 0);
+            self.needs_redraw = true;
+            self.redraw_region = RedrawRegion::Full;
+            self.sync_keep_awake();
+        }
+    }
+
+    /// Keep the device awake (and optionally the backlight at full) only
+    /// while a barcode is visible, releasing both everywhere else.
+    fn sync_keep_awake(&mut self) {
+        let awake = self.foreground && self.state == AppState::Display;
+        let boost = awake && (self.settings.max_brightness_on_display || self.scan_test.is_some());
+        if let Some(ref mut p) = self.power {
+            p.keep_awake(awake);
+            p.boost_backlight(boost);
+        }
+    }
+
+    /// Write the settings, telling the user if that failed. The whole
+    /// screen is redrawn for the message.
     pub fn save_settings(&mut self) {
+        let result = match self.storage {
+            Some(ref mut s) => s.save_settings(&self.settings),
+            None => Err(StorageError::NotMounted),
+        };
+        if let Err(e) = result {
+            log::warn!("Failed to save settings: {:?}", e);
+            self.status_message = Some(String::from(self.tr(e.message())));
+            self.redraw_region = RedrawRegion::Full;
+        }
+    }
+
+    /// Hydrate `saved_codes` from PDDB if the cache is stale.
+    pub fn ensure_codes_loaded(&mut self) {
+        if !self.codes_stale {
+            return;
+        }
+        if let Some(ref mut s) = self.storage {
+            self.saved_codes = s.load_codes();
+            self.saved_names = self.saved_codes.iter().map(|c| c.name.clone()).collect();
+            self.thumbnails.borrow_mut().clear();
+        }
+        self.codes_stale = false;
+    }
+
+    /// Write `saved_codes` to PDDB and mark the cache for a reload on next use.
+    /// Returns an error message if the write didn't happen.
+    fn persist_codes(&mut self) -> Result<(), Str> {
+        self.saved_names = self.saved_codes.iter().map(|c| c.name.clone()).collect();
+        self.thumbnails.borrow_mut().clear();
+        match self.storage {
+            Some(ref mut s) => {
+                let result = s.save_codes(&self.saved_codes);
+                self.codes_stale = true;
+                result.map_err(StorageError::message)
+            }
+            None => Err(StorageError::NotMounted.message()),
+        }
+    }
+
+    /// Add the lines waiting in the PDDB import key to the saved list. Bad
+    /// lines and names already saved are skipped and logged by line number.
+    fn import_gtins(&mut self) {
+        let text = match self.storage.as_mut().and_then(|s| s.load_import()) {
+            Some(text) => text,
+            None => {
+                self.status_message = Some(String::from(self.tr(Str::NothingToImport)));
+                return;
+            }
+        };
+        self.ensure_codes_loaded();
+        let before = self.saved_codes.len();
+        let mut skipped = Vec::new();
+        for (n, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            match parse_import_line(line) {
+                Ok(code) if self.saved_codes.iter().any(|c| c.name == code.name) => {
+                    log::warn!("Import line {}: {:?} is already saved", n + 1, code.name);
+                    skipped.push(n + 1);
+                }
+                Ok(code) => self.saved_codes.push(code),
+                Err(e) => {
+                    log::warn!("Import line {}: {}", n + 1, e);
+                    skipped.push(n + 1);
+                }
+            }
+        }
+        let added = self.saved_codes.len() - before;
+        if added > 0 {
+            let names = self.saved_codes[before..].iter().map(|c| c.name.clone()).collect();
+            self.push_undo(UndoEntry::ImportedCodes(names));
+            if let Err(e) = self.persist_codes() {
+                self.status_message = Some(String::from(self.tr(e)));
+                return;
+            }
+        }
         if let Some(ref mut s) = self.storage {
-            s.save_settings(&self.settings);
+            s.clear_import();
+        }
+
+        let mut msg = alloc::format!("{} {}", self.tr(Str::Imported), added);
+        if !skipped.is_empty() {
+            let lines: Vec<String> = skipped.iter().map(|n| alloc::format!("{}", n)).collect();
+            msg.push_str(&alloc::format!(", {} {}", self.tr(Str::SkippedLines), lines.join(",")));
+        }
+        self.status_message = Some(msg);
+    }
+
+    /// Write the whole saved list to the PDDB export key as tab-separated
+    /// rows, which `import_gtins` reads back.
+    fn export_codes(&mut self) {
+        self.ensure_codes_loaded();
+        let rows: Vec<String> = self.saved_codes.iter().map(export_row).collect();
+        let mut text = rows.join("\n");
+        text.push('\n');
+        let result = match self.storage {
+            Some(ref mut s) => s.save_export(&text),
+            None => Err(StorageError::NotMounted),
+        };
+        self.status_message = Some(match result {
+            Ok(()) => alloc::format!("{} {}", self.tr(Str::Exported), rows.len()),
+            Err(e) => String::from(self.tr(e.message())),
+        });
+    }
+
+    fn push_undo(&mut self, entry: UndoEntry) {
+        if self.undo.len() == UNDO_DEPTH {
+            self.undo.remove(0);
+        }
+        self.undo.push(entry);
+    }
+
+    /// Restore the most recent undo entry that applies to the current screen.
+    fn undo(&mut self) {
+        let wanted = |e: &UndoEntry| match e {
+            UndoEntry::Input(_) => self.state == AppState::Input,
+            UndoEntry::RemovedCodes(_) | UndoEntry::ImportedCodes(_) => self.state == AppState::LoadList,
+        };
+        let pos = match self.undo.iter().rposition(wanted) {
+            Some(pos) => pos,
+            None => {
+                self.status_message = Some(String::from(self.tr(Str::NothingToUndo)));
+                return;
+            }
+        };
+        match self.undo.remove(pos) {
+            UndoEntry::Input(text) => {
+                self.input_text = text;
+                self.last_error = None;
+            }
+            UndoEntry::RemovedCodes(removed) => {
+                // Ascending indices, so each lands where it was unless the
+                // list has since shrunk
+                for (i, code) in removed {
+                    let i = i.min(self.saved_codes.len());
+                    self.saved_codes.insert(i, code);
+                }
+                if let Err(e) = self.persist_codes() {
+                    self.status_message = Some(String::from(self.tr(e)));
+                }
+                self.category_filter = CategoryFilter::All;
+                self.load_index = 0;
+            }
+            UndoEntry::ImportedCodes(names) => {
+                self.saved_codes.retain(|c| !names.contains(&c.name));
+                if let Err(e) = self.persist_codes() {
+                    self.status_message = Some(String::from(self.tr(e)));
+                }
+                self.category_filter = CategoryFilter::All;
+                self.load_index = 0;
+            }
+        }
+    }
+
+    /// First free name of the form "name (copy)", "name (copy 2)", ...
+    fn copy_name(&self, name: &str) -> String {
+        let taken = |candidate: &str| self.saved_codes.iter().any(|c| c.name == candidate);
+        let mut candidate = alloc::format!("{} (copy)", name);
+        let mut n = 2;
+        while taken(&candidate) {
+            candidate = alloc::format!("{} (copy {})", name, n);
+            n += 1;
         }
+        candidate
+    }
+
+    pub fn saved_count(&self) -> usize {
+        self.saved_names.len()
     }
 
     pub fn save_state(&mut self) {
         self.save_settings();
+        // The save prompts come back to the code they were saving
+        let showing = matches!(
+            self.state,
+            AppState::Display | AppState::SavePrompt | AppState::SaveNameEntry | AppState::ConfirmDuplicate
+        )
+            && self.self_test_index.is_none();
+        let screen = match self.state {
+            AppState::Input | AppState::ConfirmDiscard | AppState::Keypad => StartScreen::NewBarcode,
+            AppState::LoadList | AppState::CategoryEntry => StartScreen::Saved,
+            _ => StartScreen::Menu,
+        };
+        // In private mode, keep the screen but not what was typed or shown
+        let private = self.settings.private_mode;
+        let last = Snapshot {
+            screen,
+            input_text: if private { String::new() } else { self.input_text.clone() },
+            load_index: self.load_index,
+            shown: match self.barcode {
+                Some(ref b) if showing && !private => Some((self.barcode_text.clone(), b.format)),
+                _ => None,
+            },
+        };
+        if let Some(ref mut s) = self.storage {
+            if let Err(e) = s.save_last(&last) {
+                log::warn!("Failed to save the last screen: {:?}", e);
+            }
+        }
+    }
+
+    pub fn invalidate_render_cache(&mut self) {
+        *self.render_cache.get_mut() = None;
+    }
+
+    /// The text that will actually be encoded, after optional trimming.
+    pub fn effective_input(&self) -> &str {
+        if self.settings.trim_input {
+            self.input_text.trim()
+        } else {
+            &self.input_text
+        }
+    }
+
+    /// The effective input with the prefix and suffix settings around it.
+    pub fn payload(&self) -> String {
+        alloc::format!("{}{}{}", self.settings.prefix, self.effective_input(), self.settings.suffix)
+    }
+
+    /// Look up a UI string in the current language.
+    pub fn tr(&self, s: Str) -> &'static str {
+        s.get(self.settings.language)
+    }
+
+    /// Lowercase character that blocks encoding because Code 39 case
+    /// folding is off.
+    fn unfoldable_char(&self, text: &str, format: BarcodeFormat) -> Option<char> {
+        if format == BarcodeFormat::Code39 && self.settings.code39_preserve_case {
+            text.chars().find(|c| c.is_ascii_lowercase())
+        } else {
+            None
+        }
+    }
+
+    /// Whether the effective input can be encoded in the active format, and
+    /// a stacked second payload in its detected one.
+    pub fn input_is_valid(&self) -> bool {
+        let text = self.payload();
+        match text.split_once(STACK_DELIMITER) {
+            Some((first, second)) => {
+                self.payload_is_valid(first, self.active_format())
+                    && self.payload_is_valid(second, barcode_encode::auto_detect(second))
+            }
+            None => self.payload_is_valid(&text, self.active_format()),
+        }
+    }
+
+    fn payload_is_valid(&self, text: &str, format: BarcodeFormat) -> bool {
+        if format == BarcodeFormat::Code128 && self.settings.code128_latin1 {
+            return self.first_invalid_char(text, format).is_none();
+        }
+        if self.strips(format) {
+            return barcode_encode::is_valid(&barcode_encode::digits_only(text), format);
+        }
+        if format == BarcodeFormat::Code39 && !self.settings.code39_preserve_case {
+            return barcode_encode::is_valid(&text.to_ascii_uppercase(), format);
+        }
+        barcode_encode::is_valid(text, format)
+    }
+
+    fn first_invalid_char(&self, text: &str, format: BarcodeFormat) -> Option<usize> {
+        if self.strips(format) {
+            // Only too many digits is left to go wrong; point at the first extra one
+            let bad = barcode_encode::first_invalid_char(&barcode_encode::digits_only(text), format)?;
+            return text.chars().enumerate().filter(|(_, c)| c.is_ascii_digit()).nth(bad).map(|(i, _)| i);
+        }
+        if format == BarcodeFormat::Code39 && !self.settings.code39_preserve_case {
+            return barcode_encode::first_invalid_char(&text.to_ascii_uppercase(), format);
+        }
+        if format == BarcodeFormat::Code128 && self.settings.code128_latin1 {
+            return text.chars().position(|c| (c as u32) > 255);
+        }
+        barcode_encode::first_invalid_char(text, format)
+    }
+
+    /// Position in `input_text`, in chars, of the first character that makes
+    /// the input invalid, for the Input screen to highlight. `None` when the
+    /// problem is in the prefix or suffix, or isn't one character.
+    pub fn invalid_input_index(&self) -> Option<usize> {
+        let payload = self.payload();
+        let bad = match payload.split_once(STACK_DELIMITER) {
+            Some((first, second)) => self.first_invalid_char(first, self.active_format()).or_else(|| {
+                self.first_invalid_char(second, barcode_encode::auto_detect(second))
+                    .map(|i| i + first.chars().count() + STACK_DELIMITER.len())
+            }),
+            None => self.first_invalid_char(&payload, self.active_format()),
+        }?;
+        // Back from the payload to what was typed
+        let trimmed = if self.settings.trim_input {
+            self.input_text.chars().count() - self.input_text.trim_start().chars().count()
+        } else {
+            0
+        };
+        let index = bad.checked_sub(self.settings.prefix.chars().count())? + trimmed;
+        (index < self.input_text.chars().count()).then_some(index)
+    }
+
+    /// Whether generating drops the non-digits from input for `format`.
+    fn strips(&self, format: BarcodeFormat) -> bool {
+        self.settings.strip_nondigits && format.is_numeric()
+    }
+
+    /// Longest input the Input screen accepts. Fixed-length formats stop at
+    /// their real limit, unless separators will be stripped; auto-detect
+    /// allows the longest, Code 128's.
+    pub fn max_input_len(&self) -> usize {
+        let affixes = self.settings.prefix.chars().count() + self.settings.suffix.chars().count();
+        let max = if self.settings.auto_format {
+            MAX_CODE128_LEN
+        } else if self.strips(self.settings.format) {
+            MAX_TEXT_LEN
+        } else {
+            self.format_max_len()
+        };
+        max.saturating_sub(affixes)
+    }
+
+    /// Widest symbol, in modules, that fits the canvas at 1px per module
+    /// inside the side margins. Anything wider is clipped and won't scan.
+    fn max_display_modules(&self) -> usize {
+        (self.canvas_width.get().max(0) as usize).saturating_sub(2 * DISPLAY_MARGIN)
+    }
+
+    fn format_max_len(&self) -> usize {
+        match self.settings.format {
+            BarcodeFormat::Code128 => MAX_CODE128_LEN,
+            BarcodeFormat::Ean13 => 13,
+            BarcodeFormat::UpcA => 12,
+            BarcodeFormat::Code32 => 9,
+            BarcodeFormat::Pzn => 7,
+            BarcodeFormat::Code39
+            | BarcodeFormat::Telepen
+            | BarcodeFormat::Std2of5
+            | BarcodeFormat::Matrix2of5 => MAX_TEXT_LEN,
+        }
     }
 
     pub fn active_format(&self) -> BarcodeFormat {
-        if self.settings.auto_format && !self.input_text.is_empty() {
-            barcode_encode::auto_detect(&self.input_text)
+        let text = self.payload();
+        let text = text.split(STACK_DELIMITER).next().unwrap_or(&text);
+        if self.settings.auto_format && !self.effective_input().is_empty() {
+            barcode_encode::auto_detect(text)
         } else {
             self.settings.format
         }
     }
 
+    /// UPC-A number of an EAN-13 barcode whose first digit is 0.
+    pub fn upc_form(&self, barcode: &Barcode) -> Option<String> {
+        match barcode.format {
+            BarcodeFormat::Ean13 => barcode_encode::ean13_to_upca(&barcode.text),
+            _ => None,
+        }
+    }
+
     /// Returns false if app should quit.
     pub fn handle_key(&mut self, key: char) -> bool {
         self.needs_redraw = true;
-        match self.state {
+        self.redraw_region = RedrawRegion::Full;
+        self.status_message = None;
+        let now = self.ticktimer.as_ref().map_or(0, |t| t.elapsed_ms());
+        if key == self.repeat_key && self.ticktimer.is_some() && now.saturating_sub(self.repeat_at) < REPEAT_GAP_MS {
+            self.repeat_count = self.repeat_count.saturating_add(1);
+        } else {
+            self.repeat_key = key;
+            self.repeat_count = 1;
+        }
+        self.repeat_at = now;
+        let keep_running = match self.state {
             AppState::MainMenu => self.handle_menu_key(key),
             AppState::Input => self.handle_input_key(key),
             AppState::Display => self.handle_display_key(key),
             AppState::SavePrompt => self.handle_save_prompt_key(key),
             AppState::SaveNameEntry => self.handle_save_name_key(key),
+            AppState::ConfirmDuplicate => self.handle_confirm_duplicate_key(key),
+            AppState::ConfirmDiscard => self.handle_confirm_discard_key(key),
             AppState::LoadList => self.handle_load_key(key),
+            AppState::CategoryEntry => self.handle_category_key(key),
+            AppState::AffixEntry => self.handle_affix_key(key),
             AppState::Settings => self.handle_settings_key(key),
             AppState::Help => self.handle_help_key(key),
+            AppState::FormatInfo => self.handle_format_info_key(key),
+            AppState::ComposeGtin => self.handle_compose_key(key),
+            AppState::Keypad => self.handle_keypad_key(key),
+        };
+        self.sync_keep_awake();
+        keep_running
+    }
+
+    /// How far a held key moves: 1 for the first few presses of the same
+    /// key in quick succession, then 3, then 5. Always 1 with `key_repeat`
+    /// off, or for taps further apart than `REPEAT_GAP_MS`.
+    fn repeat_step(&self) -> usize {
+        if !self.settings.key_repeat {
+            return 1;
+        }
+        match self.repeat_count {
+            0..=4 => 1,
+            5..=9 => 3,
+            _ => 5,
         }
     }
 
     fn handle_menu_key(&mut self, key: char) -> bool {
         let items = MenuItem::all();
+        let prev_index = self.menu_index;
         match key {
             KEY_UP => {
                 if self.menu_index > 0 {
                     self.menu_index -= 1;
                 }
+                self.redraw_region = RedrawRegion::MenuRows(prev_index, self.menu_index);
             }
             KEY_DOWN => {
                 if self.menu_index < items.len() - 1 {
                     self.menu_index += 1;
                 }
+                self.redraw_region = RedrawRegion::MenuRows(prev_index, self.menu_index);
             }
             KEY_ENTER => match items[self.menu_index] {
                 MenuItem::NewBarcode => {
                     self.input_text.clear();
                     self.state = AppState::Input;
                 }
+                MenuItem::ComposeGtin | MenuItem::PriceCode => {
+                    self.compose_prefix.clear();
+                    self.compose_item.clear();
+                    self.compose_on_item = false;
+                    self.compose_price = items[self.menu_index] == MenuItem::PriceCode;
+                    self.state = AppState::ComposeGtin;
+                }
                 MenuItem::SavedCodes => {
+                    self.ensure_codes_loaded();
                     self.load_index = 0;
                     self.state = AppState::LoadList;
                 }
+                MenuItem::SelfTest => self.show_self_test(0),
                 MenuItem::Settings => {
                     self.settings_index = 0;
                     self.state = AppState::Settings;
                 }
-                MenuItem::Help => self.state = AppState::Help,
+                MenuItem::Help => {
+                    self.help_scroll = 0;
+                    self.state = AppState::Help;
+                }
             },
             'n' | 'N' => {
                 self.input_text.clear();
                 self.state = AppState::Input;
             }
+            'r' | 'R' if self.barcode.is_some() && !self.settings.private_mode => self.state = AppState::Display,
             'q' | 'Q' => return false,
             _ => self.needs_redraw = false,
         }
@@ -201,6 +1366,8 @@ impl BarcodeApp {
     }
 
     fn handle_input_key(&mut self, key: char) -> bool {
+        // Everything except leaving the screen only touches the input box and status line
+        self.redraw_region = RedrawRegion::InputText;
         match key {
             KEY_ENTER => {
                 if !self.input_text.is_empty() {
@@ -208,32 +1375,85 @@ impl BarcodeApp {
                 }
             }
             KEY_BACKSPACE => {
-                self.input_text.pop();
+                if !self.input_text.is_empty() {
+                    self.push_undo(UndoEntry::Input(self.input_text.clone()));
+                }
+                for _ in 0..self.repeat_step() {
+                    self.input_text.pop();
+                }
+                self.last_error = None;
+            }
+            KEY_LEFT => self.undo(),
+            KEY_CENTER => {
+                if !self.input_text.is_empty() {
+                    self.preview_input();
+                }
+            }
+            KEY_RIGHT if self.settings.auto_format => {
+                // Take the runner-up shown on the status line
+                let text = self.payload();
+                let payload = text.split(STACK_DELIMITER).next().unwrap_or(&text);
+                if let Some(&alt) = barcode_encode::auto_detect_ranked(payload).get(1) {
+                    self.settings.auto_format = false;
+                    self.settings.format = alt;
+                    self.last_error = None;
+                }
+            }
+            KEY_UP => self.append_check_digit(),
+            KEY_DOWN => {
+                self.redraw_region = RedrawRegion::Full;
+                self.show_format_info(self.active_format());
             }
             'q' | 'Q' if self.input_text.is_empty() => {
+                self.redraw_region = RedrawRegion::Full;
                 self.state = AppState::MainMenu;
             }
+            'k' | 'K' if !self.settings.auto_format && self.settings.format.is_numeric() => {
+                self.redraw_region = RedrawRegion::Full;
+                self.state = AppState::Keypad;
+            }
+            // Where Q can't be typed, ask whether to drop the text and leave
+            'q' | 'Q' if !self.settings.auto_format && self.settings.format.is_numeric() => {
+                self.redraw_region = RedrawRegion::Full;
+                self.state = AppState::ConfirmDiscard;
+            }
             // F1-F4: Set format
             '\u{F001}' => {
                 self.settings.auto_format = false;
                 self.settings.format = BarcodeFormat::Code128;
+                self.last_error = None;
             }
             '\u{F002}' => {
                 self.settings.auto_format = false;
                 self.settings.format = BarcodeFormat::Code39;
+                self.last_error = None;
             }
             '\u{F003}' => {
                 self.settings.auto_format = false;
                 self.settings.format = BarcodeFormat::Ean13;
+                self.last_error = None;
             }
             '\u{F004}' => {
                 self.settings.auto_format = false;
                 self.settings.format = BarcodeFormat::UpcA;
+                self.last_error = None;
             }
             _ => {
-                if key.is_ascii_graphic() || key == ' ' {
-                    if self.input_text.len() < MAX_TEXT_LEN {
+                if (key.is_ascii_graphic() || key == ' ')
+                    && !key.is_ascii_digit()
+                    && !(self.settings.strip_nondigits && matches!(key, ' ' | '-'))
+                    && !self.settings.auto_format
+                    && self.settings.format.is_numeric()
+                    && !(self.settings.format == BarcodeFormat::Ean13 && matches!(key, 'x' | 'X'))
+                {
+                    self.status_message = Some(String::from(self.tr(Str::DigitsOnly)));
+                } else if key.is_ascii_graphic() || key == ' ' || (self.settings.code128_latin1 && is_latin1_graphic(key)) {
+                    let max = self.max_input_len();
+                    if self.input_text.chars().count() < max {
                         self.input_text.push(key);
+                        self.last_error = None;
+                    } else {
+                        self.status_message = Some(alloc::format!("{} ({})", self.tr(Str::MaxLength), max));
                     }
                 } else {
                     self.needs_redraw = false;
@@ -243,24 +1463,364 @@ impl BarcodeApp {
         true
     }
 
-    fn generate_barcode(&mut self) {
-        let format = self.active_format();
-        match barcode_encode::encode(&self.input_text, format) {
-            Some(barcode) => {
-                self.barcode_text = self.input_text.clone();
-                self.barcode = Some(barcode);
-                self.state = AppState::Display;
+    /// Complete a partial GTIN in place: 12 digits get the EAN-13 check
+    /// digit, 11 the UPC-A one. With auto-detect on the length decides.
+    fn append_check_digit(&mut self) {
+        let text = self.effective_input();
+        let digits: Vec<u8> = text.bytes().map(|b| b.wrapping_sub(b'0')).collect();
+        let format = if self.settings.auto_format {
+            match digits.len() {
+                12 => BarcodeFormat::Ean13,
+                _ => BarcodeFormat::UpcA,
             }
-            None => {
-                log::warn!("Failed to encode barcode: invalid input for {:?}", format);
+        } else {
+            self.settings.format
+        };
+        let check = match (format, digits.len()) {
+            _ if digits.iter().any(|&d| d > 9) => None,
+            (BarcodeFormat::Ean13, 12) => Some(barcode_encode::ean13_check_digit(&digits)),
+            (BarcodeFormat::UpcA, 11) => Some(barcode_encode::upc_check_digit(&digits)),
+            _ => None,
+        };
+        match check {
+            Some(check) => {
+                let completed = alloc::format!("{}{}", text, check);
+                let previous = core::mem::replace(&mut self.input_text, completed);
+                self.push_undo(UndoEntry::Input(previous));
+                self.last_error = None;
+                self.status_message = Some(alloc::format!("{}: {}", self.tr(Str::CheckDigitAdded), check));
+            }
+            None => self.status_message = Some(String::from(self.tr(Str::CheckDigitNeedsDigits))),
+        }
+    }
+
+    fn generate_barcode(&mut self) {
+        self.from_saved = false;
+        let format = self.active_format();
+        if self.strips(format) && self.input_text.chars().any(|c| !c.is_ascii_digit()) {
+            // Left on the Input screen gets the separators back
+            self.push_undo(UndoEntry::Input(self.input_text.clone()));
+            self.input_text = barcode_encode::digits_only(&self.input_text);
+        }
+        self.apply_bar_size(format);
+        self.encode_as(self.payload(), format);
+        if self.settings.autosave && !self.settings.private_mode && self.state == AppState::Display {
+            self.autosave();
+        }
+    }
+
+    /// Add the code just generated to the saved list, unless the same text
+    /// and format is saved already.
+    fn autosave(&mut self) {
+        let format = match self.barcode {
+            Some(ref b) => b.format,
+            None => return,
+        };
+        self.ensure_codes_loaded();
+        if self.saved_codes.iter().any(|c| c.text == self.barcode_text && c.format == format) {
+            return;
+        }
+        let name = self.autosave_name(format);
+        self.saved_codes.push(SavedBarcode {
+            name: name.clone(),
+            text: self.barcode_text.clone(),
+            format,
+            category: Some(String::from(AUTOSAVE_CATEGORY)),
+            created: now_secs(),
+        });
+        // Keep a too-wide or check digit message from the encode
+        let msg = match self.persist_codes() {
+            Ok(()) => alloc::format!("{} {}", self.tr(Str::AutoSavedAs), name),
+            Err(e) => String::from(self.tr(e)),
+        };
+        self.status_message.get_or_insert(msg);
+    }
+
+    /// First free name of the form "EAN13 001", "EAN13 002", ...
+    fn autosave_name(&self, format: BarcodeFormat) -> String {
+        let mut n = 1;
+        loop {
+            let candidate = alloc::format!("{} {:03}", format.short(), n);
+            if !self.saved_codes.iter().any(|c| c.name == candidate) {
+                return candidate;
             }
+            n += 1;
+        }
+    }
+
+    /// Delete every auto-saved code, with one undo step for the lot.
+    fn purge_autosaved(&mut self) {
+        let (purged, kept): (Vec<_>, Vec<_>) = core::mem::take(&mut self.saved_codes)
+            .into_iter()
+            .enumerate()
+            .partition(|(_, c)| c.category.as_deref() == Some(AUTOSAVE_CATEGORY));
+        self.saved_codes = kept.into_iter().map(|(_, c)| c).collect();
+        let removed = purged.len();
+        if removed > 0 {
+            self.push_undo(UndoEntry::RemovedCodes(purged));
+            if let Err(e) = self.persist_codes() {
+                self.status_message = Some(String::from(self.tr(e)));
+                return;
+            }
+            self.category_filter = CategoryFilter::All;
+            self.load_index = 0;
+        }
+        self.status_message = Some(alloc::format!("{} {}", self.tr(Str::PurgedAutoSaved), removed));
+    }
+
+    /// Encode the payload for the Input screen preview without leaving Input.
+    /// Only the first code of a stacked pair is previewed.
+    fn preview_input(&mut self) {
+        let format = self.active_format();
+        let text = self.payload();
+        let first = text.split(STACK_DELIMITER).next().unwrap_or(&text);
+        let first = if self.settings.trim_input { first.trim() } else { first };
+        match self.encode_payload(first, format) {
+            Ok((barcode, _)) => {
+                self.last_error = None;
+                self.inline_preview = Some((text, barcode));
+            }
+            Err(e) => {
+                self.inline_preview = None;
+                self.last_error = Some(e);
+            }
+        }
+    }
+
+    /// The Input screen preview, if it still matches the input and format.
+    pub fn input_preview(&self) -> Option<&Barcode> {
+        self.inline_preview
+            .as_ref()
+            .filter(|(text, barcode)| *text == self.payload() && barcode.format == self.active_format())
+            .map(|(_, barcode)| barcode)
+    }
+
+    /// Replace the shown code with random data its format accepts.
+    #[cfg(feature = "debug-overlay")]
+    fn show_random(&mut self) {
+        let format = match self.barcode.as_ref() {
+            Some(barcode) => barcode.format,
+            None => return,
+        };
+        let text = barcode_encode::sample_payload(format, &mut self.random_seed);
+        self.self_test_index = None;
+        self.from_saved = false;
+        self.tile_mode = false;
+        self.input_text = text.clone();
+        self.encode_as(text, format);
+    }
+
+    /// On a format change, switch to the bar size the user last picked for
+    /// `format` this session, or its default. The first format of a session
+    /// keeps the size loaded from the settings, so that isn't overwritten.
+    fn apply_bar_size(&mut self, format: BarcodeFormat) {
+        if self.sized_format == Some(format) {
+            return;
+        }
+        if self.sized_format.is_none() {
+            self.remember_bar_size(format);
+            return;
+        }
+        let (width, height) = self
+            .bar_size_overrides
+            .iter()
+            .find(|&&(f, _, _)| f == format)
+            .map(|&(_, w, h)| (w, h))
+            .unwrap_or_else(|| format.default_bar_size());
+        self.settings.bar_width = width;
+        self.settings.bar_height = height;
+        self.sized_format = Some(format);
+    }
+
+    /// Display screen bar size change; sticks for the shown format this session.
+    fn resize_bars(&mut self, width: u8, height: u16) {
+        self.settings.bar_width = width;
+        self.settings.bar_height = height;
+        self.invalidate_render_cache();
+        if let Some(format) = self.barcode.as_ref().map(|b| b.format) {
+            self.remember_bar_size(format);
+        }
+        self.save_settings();
+    }
+
+    /// Record the current bar size as the user's choice for `format`.
+    fn remember_bar_size(&mut self, format: BarcodeFormat) {
+        let size = (format, self.settings.bar_width, self.settings.bar_height);
+        match self.bar_size_overrides.iter_mut().find(|(f, _, _)| *f == format) {
+            Some(entry) => *entry = size,
+            None => self.bar_size_overrides.push(size),
+        }
+        self.sized_format = Some(format);
+    }
+
+    /// Encode `text` as `format` and show it, or set `last_error`. Text
+    /// with `STACK_DELIMITER` in it becomes two stacked codes.
+    fn encode_as(&mut self, text: String, format: BarcodeFormat) {
+        let (first, second) = match text.split_once(STACK_DELIMITER) {
+            Some((first, second)) if self.settings.trim_input => (first.trim(), Some(second.trim())),
+            Some((first, second)) => (first, Some(second)),
+            None => (text.as_str(), None),
+        };
+        let stacked = match second {
+            Some(second) => match self.encode_payload(second, barcode_encode::auto_detect(second)) {
+                Ok((barcode, _)) => Some(barcode),
+                Err(e) => {
+                    self.last_error = Some(e);
+                    return;
+                }
+            },
+            None => None,
+        };
+        match self.encode_payload(first, format) {
+            Ok((barcode, checked)) => {
+                // Same text can come out differently, e.g. after a ratio change
+                self.invalidate_render_cache();
+                // No 2D format to fall back on yet, so just say why it won't scan
+                let too_wide = core::iter::once(&barcode)
+                    .chain(stacked.iter())
+                    .any(|b| b.modules.len() > self.max_display_modules());
+                if too_wide {
+                    self.status_message = Some(String::from(self.tr(Str::TooWide)));
+                } else if checked {
+                    self.status_message = Some(String::from(self.tr(Str::CheckDigitOk)));
+                }
+                self.redraw_region = RedrawRegion::Full;
+                self.barcode_text = text.clone();
+                self.upc_view = false;
+                self.barcode = Some(barcode);
+                self.stacked = stacked;
+                self.state = AppState::Display;
+            }
+            Err(e) => self.last_error = Some(e),
+        }
+    }
+
+    /// Encode one payload as `format` with the check digit and suffix
+    /// settings applied. Also returns whether a typed check digit was verified.
+    fn encode_payload(&self, text: &str, format: BarcodeFormat) -> Result<(Barcode, bool), String> {
+        if let Some(c) = self.unfoldable_char(text, format) {
+            return Err(alloc::format!("{}: '{}' {}", format.label(), c, self.tr(Str::NeedsExtended39)));
+        }
+        // A mismatch is the encoder's to reject; this is just for the OK message
+        let checked = self.settings.check_digit_mode == CheckMode::Require
+            && barcode_encode::verify_check_digit(text, format) == Ok(true);
+        let mut encoded = String::from(text);
+        if format == BarcodeFormat::Ean13 && self.settings.ean_zero_pad {
+            encoded = barcode_encode::ean13_zero_pad(text);
+        }
+        let mut check_char = false;
+        if matches!(format, BarcodeFormat::Std2of5 | BarcodeFormat::Matrix2of5) && self.settings.std2of5_check {
+            if let Some(check) = barcode_encode::std2of5_check_digit(text) {
+                encoded.push(check);
+                check_char = true;
+            }
+        }
+        if format == BarcodeFormat::Code128 && self.settings.append_enter {
+            encoded.push('\r');
+        }
+        let options = EncodeOptions {
+            wide_ratio: self.settings.wide_ratio,
+            code39_gap: self.settings.code39_gap,
+            check_mode: self.settings.check_digit_mode,
+            code128_force_b: self.settings.code128_force_b,
+            code128_latin1: self.settings.code128_latin1,
+        };
+        match barcode_encode::encode_with(&encoded, format, options) {
+            Ok(mut barcode) => {
+                if check_char && !self.settings.show_check_char {
+                    barcode.text.pop();
+                }
+                Ok((barcode, checked))
+            }
+            Err(e) => {
+                log::warn!("Failed to encode barcode as {:?}: {:?}", format, e);
+                Err(alloc::format!("{}: {}", format.label(), e))
+            }
+        }
+    }
+
+    /// Encode `saved_codes[i]` and show it, with Left/Right on the Display
+    /// moving to its neighbours in the saved list.
+    fn show_saved(&mut self, i: usize) {
+        let code = &self.saved_codes[i];
+        let (text, format) = (code.text.clone(), code.format);
+        self.input_text = text.clone();
+        self.settings.format = format;
+        self.settings.auto_format = false;
+        // Saved with any prefix and suffix already in it
+        self.apply_bar_size(format);
+        self.encode_as(text, format);
+        self.from_saved = self.state == AppState::Display;
+        if self.from_saved {
+            // Stepping from the Display onto a code that no longer encodes
+            if let Some(e) = self.last_error.take() {
+                self.status_message = Some(e);
+            }
+        }
+    }
+
+    fn show_self_test(&mut self, index: usize) {
+        let (text, format) = SELF_TEST_SAMPLES[index];
+        match barcode_encode::encode(text, format) {
+            Ok(barcode) => {
+                self.barcode_text = String::from(text);
+                self.barcode = Some(barcode);
+                self.stacked = None;
+                self.upc_view = false;
+                self.from_saved = false;
+                self.self_test_index = Some(index);
+                self.state = AppState::Display;
+            }
+            Err(e) => log::warn!("Self test sample {:?} failed to encode: {:?}", text, e),
         }
     }
 
     fn handle_display_key(&mut self, key: char) -> bool {
+        // Any key ends a scan test and does nothing else
+        if self.scan_test.is_some() {
+            self.scan_test = None;
+            return true;
+        }
+        // Browsing saved codes, Left/Right step through the list instead of resizing
+        if self.from_saved && matches!(key, KEY_LEFT | KEY_RIGHT) {
+            let visible = self.visible_codes();
+            if !visible.is_empty() {
+                let count = visible.len();
+                self.load_index = if key == KEY_RIGHT {
+                    (self.load_index + 1) % count
+                } else {
+                    (self.load_index + count - 1) % count
+                };
+                self.show_saved(visible[self.load_index]);
+            }
+            return true;
+        }
+        // In self test, Left/Right cycle through the samples instead of resizing
+        if let Some(index) = self.self_test_index {
+            let count = SELF_TEST_SAMPLES.len();
+            match key {
+                KEY_RIGHT => {
+                    self.show_self_test((index + 1) % count);
+                    return true;
+                }
+                KEY_LEFT => {
+                    self.show_self_test((index + count - 1) % count);
+                    return true;
+                }
+                _ => {}
+            }
+        }
         match key {
-            'q' | 'Q' => self.state = AppState::MainMenu,
+            'q' | 'Q' => {
+                self.self_test_index = None;
+                self.from_saved = false;
+                self.tile_mode = false;
+                self.state = AppState::MainMenu;
+            }
             'n' | 'N' => {
+                self.self_test_index = None;
+                self.from_saved = false;
+                self.tile_mode = false;
                 self.input_text.clear();
                 self.state = AppState::Input;
             }
@@ -268,30 +1828,49 @@ impl BarcodeApp {
                 self.save_name.clear();
                 self.state = AppState::SavePrompt;
             }
-            KEY_UP => {
-                if self.settings.bar_height < 300 {
-                    self.settings.bar_height += 20;
-                    self.save_settings();
+            't' | 'T' => self.tile_mode = !self.tile_mode,
+            'p' | 'P' => self.start_scan_test(),
+            #[cfg(feature = "debug-overlay")]
+            'b' | 'B' => self.show_bits = !self.show_bits,
+            #[cfg(feature = "debug-overlay")]
+            'r' | 'R' => self.show_ruler = !self.show_ruler,
+            #[cfg(feature = "debug-overlay")]
+            'x' | 'X' => self.show_random(),
+            'u' | 'U' => {
+                if self.barcode.as_ref().and_then(|b| self.upc_form(b)).is_some() {
+                    self.upc_view = !self.upc_view;
+                } else {
+                    self.status_message = Some(String::from(self.tr(Str::NotUpcA)));
                 }
             }
-            KEY_DOWN => {
-                if self.settings.bar_height > 80 {
-                    self.settings.bar_height -= 20;
-                    self.save_settings();
-                }
+            'm' | 'M' => {
+                self.settings.mirror = !self.settings.mirror;
+                self.invalidate_render_cache();
+                self.save_settings();
             }
-            KEY_RIGHT => {
-                if self.settings.bar_width < 4 {
-                    self.settings.bar_width += 1;
-                    self.save_settings();
-                }
+            'v' | 'V' => {
+                let result = self.barcode.as_ref().and_then(|b| b.verify());
+                let msg = match result {
+                    Some(true) => Str::Verified,
+                    Some(false) => Str::Mismatch,
+                    None => Str::VerifyUnavailable,
+                };
+                self.status_message = Some(String::from(self.tr(msg)));
             }
-            KEY_LEFT => {
-                if self.settings.bar_width > 1 {
-                    self.settings.bar_width -= 1;
-                    self.save_settings();
-                }
+            // Auto height leaves nothing to adjust
+            KEY_UP if self.settings.bar_height < 300 && !self.settings.auto_height => {
+                self.resize_bars(self.settings.bar_width, self.settings.bar_height + 20)
+            }
+            KEY_DOWN if self.settings.bar_height > 80 && !self.settings.auto_height => {
+                self.resize_bars(self.settings.bar_width, self.settings.bar_height - 20)
+            }
+            KEY_RIGHT if self.settings.bar_width < 4 => {
+                self.resize_bars(self.settings.bar_width + 1, self.settings.bar_height)
             }
+            KEY_LEFT if self.settings.bar_width > 1 => {
+                self.resize_bars(self.settings.bar_width - 1, self.settings.bar_height)
+            }
+            KEY_UP | KEY_DOWN | KEY_LEFT | KEY_RIGHT => {}
             _ => self.needs_redraw = false,
         }
         true
@@ -312,26 +1891,36 @@ impl BarcodeApp {
     fn handle_save_name_key(&mut self, key: char) -> bool {
         match key {
             KEY_ENTER => {
-                if !self.save_name.is_empty() {
-                    let code = SavedBarcode {
-                        name: self.save_name.clone(),
-                        text: self.barcode_text.clone(),
-                        format: self.barcode.as_ref().map(|b| b.format).unwrap_or(BarcodeFormat::Code128),
-                    };
-                    self.saved_codes.push(code);
-                    if let Some(ref mut s) = self.storage {
-                        s.save_codes(&self.saved_codes);
+                let name = normalize_name(&self.save_name);
+                if name.is_empty() {
+                    self.status_message = Some(String::from(self.tr(Str::NameBlank)));
+                } else {
+                    self.save_name = name;
+                    self.ensure_codes_loaded();
+                    let format = self.shown_format();
+                    let existing = self
+                        .saved_codes
+                        .iter()
+                        .find(|c| c.text == self.barcode_text && c.format == format)
+                        .map(|c| c.name.clone());
+                    match existing {
+                        Some(existing) => {
+                            self.duplicate_name = existing;
+                            self.state = AppState::ConfirmDuplicate;
+                        }
+                        None => self.save_shown(),
                     }
-                    self.state = AppState::Display;
                 }
             }
             KEY_BACKSPACE => {
-                self.save_name.pop();
+                for _ in 0..self.repeat_step() {
+                    self.save_name.pop();
+                }
             }
             'q' | 'Q' if self.save_name.is_empty() => self.state = AppState::Display,
             _ => {
                 if key.is_ascii_graphic() || key == ' ' {
-                    if self.save_name.len() < 30 {
+                    if self.save_name.len() < MAX_NAME_LEN {
                         self.save_name.push(key);
                     }
                 } else {
@@ -342,35 +1931,140 @@ impl BarcodeApp {
         true
     }
 
-    fn handle_load_key(&mut self, key: char) -> bool {
+    fn handle_keypad_key(&mut self, key: char) -> bool {
+        let index = self.keypad_index;
         match key {
-            KEY_UP => {
-                if self.load_index > 0 {
-                    self.load_index -= 1;
+            KEY_UP => self.keypad_index = (index + KEYPAD_KEYS.len() - KEYPAD_COLUMNS) % KEYPAD_KEYS.len(),
+            KEY_DOWN => self.keypad_index = (index + KEYPAD_COLUMNS) % KEYPAD_KEYS.len(),
+            KEY_LEFT => self.keypad_index = index - index % KEYPAD_COLUMNS + (index + KEYPAD_COLUMNS - 1) % KEYPAD_COLUMNS,
+            KEY_RIGHT => self.keypad_index = index - index % KEYPAD_COLUMNS + (index + 1) % KEYPAD_COLUMNS,
+            KEY_ENTER | KEY_CENTER => match KEYPAD_KEYS[index] {
+                KeypadKey::Digit(d) => self.keypad_push(d),
+                KeypadKey::Delete => {
+                    self.input_text.pop();
                 }
+                KeypadKey::Done => self.state = AppState::Input,
+            },
+            // The keyboard still works while the grid is up
+            KEY_BACKSPACE => {
+                self.input_text.pop();
+            }
+            'q' | 'Q' => self.state = AppState::Input,
+            c if c.is_ascii_digit() => self.keypad_push(c),
+            _ => self.needs_redraw = false,
+        }
+        self.last_error = None;
+        true
+    }
+
+    fn keypad_push(&mut self, digit: char) {
+        let max = self.max_input_len();
+        if self.input_text.chars().count() < max {
+            self.input_text.push(digit);
+        } else {
+            self.status_message = Some(alloc::format!("{} ({})", self.tr(Str::MaxLength), max));
+        }
+    }
+
+    fn handle_confirm_discard_key(&mut self, key: char) -> bool {
+        match key {
+            'y' | 'Y' | KEY_ENTER => {
+                // Left on the Input screen brings it back
+                let text = core::mem::take(&mut self.input_text);
+                self.push_undo(UndoEntry::Input(text));
+                self.last_error = None;
+                self.state = AppState::MainMenu;
             }
+            'n' | 'N' | 'q' | 'Q' => self.state = AppState::Input,
+            _ => self.needs_redraw = false,
+        }
+        true
+    }
+
+    fn handle_confirm_duplicate_key(&mut self, key: char) -> bool {
+        match key {
+            'y' | 'Y' | KEY_ENTER => self.save_shown(),
+            'n' | 'N' | 'q' | 'Q' => self.state = AppState::SaveNameEntry,
+            _ => self.needs_redraw = false,
+        }
+        true
+    }
+
+    fn shown_format(&self) -> BarcodeFormat {
+        self.barcode.as_ref().map(|b| b.format).unwrap_or(BarcodeFormat::Code128)
+    }
+
+    /// Add the shown code to the saved list as `save_name` and go back to the Display.
+    fn save_shown(&mut self) {
+        let code = SavedBarcode {
+            name: self.save_name.clone(),
+            text: self.barcode_text.clone(),
+            format: self.shown_format(),
+            category: None,
+            created: now_secs(),
+        };
+        self.saved_codes.push(code);
+        self.status_message = Some(match self.persist_codes() {
+            Ok(()) => alloc::format!("{} {}", self.tr(Str::SavedAs), self.save_name),
+            Err(e) => String::from(self.tr(e)),
+        });
+        self.state = AppState::Display;
+    }
+
+    fn handle_load_key(&mut self, key: char) -> bool {
+        let visible = self.visible_codes();
+        let selected = visible.get(self.load_index).copied();
+        match key {
+            KEY_UP => self.load_index = self.load_index.saturating_sub(self.repeat_step()),
             KEY_DOWN => {
-                if !self.saved_codes.is_empty() && self.load_index < self.saved_codes.len() - 1 {
+                self.load_index = (self.load_index + self.repeat_step()).min(visible.len().saturating_sub(1));
+            }
+            KEY_LEFT | KEY_RIGHT => {
+                self.cycle_category_filter(key == KEY_RIGHT);
+                self.load_index = 0;
+            }
+            KEY_ENTER => {
+                if let Some(i) = selected {
+                    self.show_saved(i);
+                }
+            }
+            'c' | 'C' => {
+                if let Some(i) = selected {
+                    let mut copy = self.saved_codes[i].clone();
+                    copy.name = self.copy_name(&copy.name);
+                    copy.created = now_secs();
+                    self.saved_codes.insert(i + 1, copy);
                     self.load_index += 1;
+                    self.status_message = Some(match self.persist_codes() {
+                        Ok(()) => alloc::format!("{} {}", self.tr(Str::SavedAs), self.saved_codes[i + 1].name),
+                        Err(e) => String::from(self.tr(e)),
+                    });
                 }
             }
-            KEY_ENTER => {
-                if !self.saved_codes.is_empty() {
-                    let code = &self.saved_codes[self.load_index];
-                    self.input_text = code.text.clone();
-                    self.settings.format = code.format;
-                    self.settings.auto_format = false;
-                    self.generate_barcode();
+            'g' | 'G' => {
+                if let Some(i) = selected {
+                    self.category_input = self.saved_codes[i].category.clone().unwrap_or_default();
+                    self.state = AppState::CategoryEntry;
                 }
             }
+            'u' | 'U' => self.undo(),
+            'i' | 'I' => self.import_gtins(),
+            'e' | 'E' => self.export_codes(),
+            'p' | 'P' => self.purge_autosaved(),
             'd' | 'D' => {
-                if !self.saved_codes.is_empty() {
-                    self.saved_codes.remove(self.load_index);
-                    if let Some(ref mut s) = self.storage {
-                        s.save_codes(&self.saved_codes);
+                if let Some(i) = selected {
+                    let code = self.saved_codes.remove(i);
+                    self.push_undo(UndoEntry::RemovedCodes(vec![(i, code)]));
+                    if let Err(e) = self.persist_codes() {
+                        self.status_message = Some(String::from(self.tr(e)));
+                    }
+                    let mut remaining = self.visible_codes().len();
+                    if remaining == 0 {
+                        self.category_filter = CategoryFilter::All;
+                        remaining = self.saved_codes.len();
                     }
-                    if self.load_index > 0 && self.load_index >= self.saved_codes.len() {
-                        self.load_index = self.saved_codes.len().saturating_sub(1);
+                    if self.load_index > 0 && self.load_index >= remaining {
+                        self.load_index = remaining.saturating_sub(1);
                     }
                 }
             }
@@ -380,36 +2074,202 @@ impl BarcodeApp {
         true
     }
 
+    fn handle_compose_key(&mut self, key: char) -> bool {
+        let step = self.repeat_step();
+        let room = match (self.compose_price, self.compose_on_item) {
+            (true, false) => self.compose_prefix.len() < 5,
+            (true, true) => self.compose_item.len() < 4,
+            // The longest GTIN here is EAN-13: 12 digits before the check
+            (false, _) => self.compose_prefix.len() + self.compose_item.len() < 12,
+        };
+        let field = if self.compose_on_item { &mut self.compose_item } else { &mut self.compose_prefix };
+        match key {
+            KEY_ENTER if !self.compose_on_item => {
+                if !self.compose_prefix.is_empty() {
+                    self.compose_on_item = true;
+                }
+            }
+            KEY_ENTER => self.finish_compose(),
+            KEY_BACKSPACE if field.is_empty() => self.compose_on_item = false,
+            KEY_BACKSPACE => {
+                for _ in 0..step {
+                    field.pop();
+                }
+            }
+            'q' | 'Q' if field.is_empty() => self.state = AppState::MainMenu,
+            _ if key.is_ascii_digit() => {
+                if room {
+                    field.push(key);
+                }
+            }
+            _ if key.is_ascii_graphic() || key == ' ' => {
+                self.status_message = Some(String::from(self.tr(Str::DigitsOnly)));
+            }
+            _ => self.needs_redraw = false,
+        }
+        true
+    }
+
+    /// Join prefix and item reference, add the check digit and encode: 11
+    /// digits as UPC-A, 12 as EAN-13.
+    fn finish_compose(&mut self) {
+        if self.compose_price {
+            match barcode_encode::variable_weight_upca(&self.compose_prefix, &self.compose_item) {
+                Ok(upc) => self.show_composed(upc, BarcodeFormat::UpcA),
+                Err(e) => self.status_message = Some(alloc::format!("{}", e)),
+            }
+            return;
+        }
+        let gtin = alloc::format!("{}{}", self.compose_prefix, self.compose_item);
+        let digits: Vec<u8> = gtin.bytes().map(|b| b - b'0').collect();
+        let (format, check) = match digits.len() {
+            11 => (BarcodeFormat::UpcA, barcode_encode::upc_check_digit(&digits)),
+            12 => (BarcodeFormat::Ean13, barcode_encode::ean13_check_digit(&digits)),
+            _ => {
+                self.status_message = Some(String::from(self.tr(Str::ComposeLength)));
+                return;
+            }
+        };
+        self.show_composed(alloc::format!("{}{}", gtin, check), format);
+    }
+
+    fn show_composed(&mut self, text: String, format: BarcodeFormat) {
+        self.input_text = text;
+        self.settings.format = format;
+        self.settings.auto_format = false;
+        // An encode error is shown on the input screen
+        self.state = AppState::Input;
+        self.generate_barcode();
+    }
+
+    fn handle_category_key(&mut self, key: char) -> bool {
+        match key {
+            KEY_ENTER => {
+                if let Some(i) = self.visible_codes().get(self.load_index).copied() {
+                    let category = self.category_input.trim();
+                    self.saved_codes[i].category = if category.is_empty() { None } else { Some(String::from(category)) };
+                    if let Err(e) = self.persist_codes() {
+                        self.status_message = Some(String::from(self.tr(e)));
+                    }
+                }
+                // The code may have left the filtered view
+                self.load_index = 0;
+                if self.visible_codes().is_empty() {
+                    self.category_filter = CategoryFilter::All;
+                }
+                self.state = AppState::LoadList;
+            }
+            KEY_BACKSPACE => {
+                for _ in 0..self.repeat_step() {
+                    self.category_input.pop();
+                }
+            }
+            'q' | 'Q' if self.category_input.is_empty() => self.state = AppState::LoadList,
+            _ => {
+                if key.is_ascii_graphic() || key == ' ' {
+                    if self.category_input.len() < 20 {
+                        self.category_input.push(key);
+                    }
+                } else {
+                    self.needs_redraw = false;
+                }
+            }
+        }
+        true
+    }
+
+    fn handle_affix_key(&mut self, key: char) -> bool {
+        match key {
+            KEY_ENTER => {
+                let affix = core::mem::take(&mut self.affix_input);
+                if self.affix_is_suffix {
+                    self.settings.suffix = affix;
+                } else {
+                    self.settings.prefix = affix;
+                }
+                self.save_settings();
+                self.state = AppState::Settings;
+            }
+            KEY_BACKSPACE => {
+                for _ in 0..self.repeat_step() {
+                    self.affix_input.pop();
+                }
+            }
+            'q' | 'Q' if self.affix_input.is_empty() => self.state = AppState::Settings,
+            _ => {
+                if key.is_ascii_graphic() || key == ' ' {
+                    if self.affix_input.len() < MAX_AFFIX_LEN {
+                        self.affix_input.push(key);
+                    }
+                } else {
+                    self.needs_redraw = false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Categories in use, named ones sorted, then `None` if any code has none.
+    pub fn categories(&self) -> Vec<Option<String>> {
+        let mut named: Vec<String> = self.saved_codes.iter().filter_map(|c| c.category.clone()).collect();
+        named.sort();
+        named.dedup();
+        let mut all: Vec<Option<String>> = named.into_iter().map(Some).collect();
+        if self.saved_codes.iter().any(|c| c.category.is_none()) {
+            all.push(None);
+        }
+        all
+    }
+
+    /// Step the saved list filter through All and each category in turn.
+    fn cycle_category_filter(&mut self, forward: bool) {
+        let mut steps: Vec<CategoryFilter> = vec![CategoryFilter::All];
+        steps.extend(self.categories().into_iter().map(CategoryFilter::Only));
+        let pos = steps.iter().position(|f| *f == self.category_filter).unwrap_or(0);
+        let next = if forward { (pos + 1) % steps.len() } else { (pos + steps.len() - 1) % steps.len() };
+        self.category_filter = steps.swap_remove(next);
+    }
+
+    /// Indices into `saved_codes` shown by the current category filter.
+    pub fn visible_codes(&self) -> Vec<usize> {
+        (0..self.saved_codes.len())
+            .filter(|&i| match self.category_filter {
+                CategoryFilter::All => true,
+                CategoryFilter::Only(ref cat) => self.saved_codes[i].category == *cat,
+            })
+            .collect()
+    }
+
     fn handle_settings_key(&mut self, key: char) -> bool {
-        // 4 settings: format, auto-detect, bar width, bar height
+        let items = SettingItem::all();
+        let prev_index = self.settings_index;
         match key {
             KEY_UP => {
-                if self.settings_index > 0 {
-                    self.settings_index -= 1;
-                }
+                self.settings_index = self.settings_index.saturating_sub(self.repeat_step());
+                self.redraw_region = RedrawRegion::SettingsRows(prev_index, self.settings_index);
             }
             KEY_DOWN => {
-                if self.settings_index < 3 {
-                    self.settings_index += 1;
-                }
+                self.settings_index = (self.settings_index + self.repeat_step()).min(items.len() - 1);
+                self.redraw_region = RedrawRegion::SettingsRows(prev_index, self.settings_index);
             }
             KEY_LEFT | KEY_RIGHT | KEY_ENTER => {
-                match self.settings_index {
-                    0 => {
+                match items[self.settings_index] {
+                    SettingItem::Format => {
                         self.settings.format = self.settings.format.next();
                     }
-                    1 => {
+                    SettingItem::AutoDetect => {
                         self.settings.auto_format = !self.settings.auto_format;
                     }
-                    2 => {
+                    SettingItem::BarWidth => {
                         if key == KEY_RIGHT || key == KEY_ENTER {
                             self.settings.bar_width = (self.settings.bar_width % 4) + 1;
                         } else {
                             self.settings.bar_width =
                                 if self.settings.bar_width <= 1 { 4 } else { self.settings.bar_width - 1 };
                         }
+                        self.remember_bar_size(self.settings.format);
                     }
-                    3 => {
+                    SettingItem::BarHeight => {
                         if key == KEY_RIGHT || key == KEY_ENTER {
                             self.settings.bar_height =
                                 (self.settings.bar_height + 20).min(300);
@@ -417,22 +2277,229 @@ impl BarcodeApp {
                             self.settings.bar_height =
                                 self.settings.bar_height.saturating_sub(20).max(80);
                         }
+                        self.remember_bar_size(self.settings.format);
+                    }
+                    SettingItem::AutoHeight => {
+                        self.settings.auto_height = !self.settings.auto_height;
+                    }
+                    SettingItem::TextSize => {
+                        self.settings.text_size = self.settings.text_size.next();
+                    }
+                    SettingItem::TrimInput => {
+                        self.settings.trim_input = !self.settings.trim_input;
+                    }
+                    SettingItem::StripNonDigits => {
+                        self.settings.strip_nondigits = !self.settings.strip_nondigits;
+                    }
+                    SettingItem::MaxBrightness => {
+                        self.settings.max_brightness_on_display = !self.settings.max_brightness_on_display;
+                    }
+                    SettingItem::ScanTestTime => {
+                        let secs = self.settings.scan_test_secs;
+                        self.settings.scan_test_secs = if key == KEY_RIGHT || key == KEY_ENTER {
+                            if secs >= 60 { 5 } else { secs + 5 }
+                        } else if secs <= 5 {
+                            60
+                        } else {
+                            secs - 5
+                        };
+                    }
+                    SettingItem::Code39Case => {
+                        self.settings.code39_preserve_case = !self.settings.code39_preserve_case;
+                    }
+                    SettingItem::Std2of5Check => {
+                        self.settings.std2of5_check = !self.settings.std2of5_check;
+                    }
+                    SettingItem::ShowCheckChar => {
+                        self.settings.show_check_char = !self.settings.show_check_char;
+                    }
+                    SettingItem::AppendEnter => {
+                        self.settings.append_enter = !self.settings.append_enter;
+                    }
+                    SettingItem::Code128ForceB => {
+                        self.settings.code128_force_b = !self.settings.code128_force_b;
+                    }
+                    SettingItem::Code128Latin1 => {
+                        self.settings.code128_latin1 = !self.settings.code128_latin1;
+                    }
+                    SettingItem::EanZeroPad => {
+                        self.settings.ean_zero_pad = !self.settings.ean_zero_pad;
+                    }
+                    SettingItem::UpcText => {
+                        self.settings.upc_text = !self.settings.upc_text;
+                    }
+                    SettingItem::WideRatio => {
+                        self.settings.wide_ratio = if self.settings.wide_ratio == 2 { 3 } else { 2 };
+                    }
+                    SettingItem::Code39Gap => {
+                        if key == KEY_RIGHT || key == KEY_ENTER {
+                            self.settings.code39_gap = self.settings.code39_gap % 3 + 1;
+                        } else {
+                            self.settings.code39_gap = if self.settings.code39_gap <= 1 { 3 } else { self.settings.code39_gap - 1 };
+                        }
+                    }
+                    SettingItem::CheckDigitMode => {
+                        self.settings.check_digit_mode = match self.settings.check_digit_mode {
+                            CheckMode::Recompute => CheckMode::Require,
+                            CheckMode::Require => CheckMode::Append,
+                            CheckMode::Append => CheckMode::Recompute,
+                        };
+                    }
+                    SettingItem::ResumeLast => {
+                        self.settings.resume_last = !self.settings.resume_last;
+                    }
+                    SettingItem::AutoSave => {
+                        self.settings.autosave = !self.settings.autosave;
+                    }
+                    SettingItem::PrivateMode => {
+                        self.settings.private_mode = !self.settings.private_mode;
+                    }
+                    SettingItem::StartScreen => {
+                        self.settings.start_screen = self.settings.start_screen.next();
+                    }
+                    SettingItem::Mirror => {
+                        self.settings.mirror = !self.settings.mirror;
+                    }
+                    SettingItem::CenterBars => {
+                        self.settings.center_bars = !self.settings.center_bars;
+                    }
+                    SettingItem::DrawFrame => {
+                        self.settings.draw_frame = !self.settings.draw_frame;
+                    }
+                    SettingItem::TileCount => {
+                        if key == KEY_RIGHT || key == KEY_ENTER {
+                            self.settings.tile_count = if self.settings.tile_count >= 12 { 2 } else { self.settings.tile_count + 1 };
+                        } else {
+                            self.settings.tile_count = if self.settings.tile_count <= 2 { 12 } else { self.settings.tile_count - 1 };
+                        }
+                    }
+                    SettingItem::KeyRepeat => {
+                        self.settings.key_repeat = !self.settings.key_repeat;
+                    }
+                    SettingItem::Prefix | SettingItem::Suffix => {
+                        self.affix_is_suffix = items[self.settings_index] == SettingItem::Suffix;
+                        self.affix_input =
+                            if self.affix_is_suffix { self.settings.suffix.clone() } else { self.settings.prefix.clone() };
+                        self.state = AppState::AffixEntry;
+                    }
+                    SettingItem::Language => {
+                        self.settings.language = self.settings.language.next();
                     }
-                    _ => {}
+                }
+                self.redraw_region = RedrawRegion::SettingsRows(prev_index, prev_index);
+                if items[self.settings_index] == SettingItem::Language {
+                    // Every label on the screen changes
+                    self.redraw_region = RedrawRegion::Full;
                 }
                 self.save_settings();
             }
+            'i' | 'I' => self.show_format_info(self.settings.format),
             'q' | 'Q' => self.state = AppState::MainMenu,
             _ => self.needs_redraw = false,
         }
         true
     }
 
+    fn show_format_info(&mut self, format: BarcodeFormat) {
+        self.info_format = format;
+        self.info_return = self.state.clone();
+        self.state = AppState::FormatInfo;
+    }
+
+    fn handle_format_info_key(&mut self, key: char) -> bool {
+        match key {
+            'q' | 'Q' | KEY_ENTER | KEY_BACKSPACE => self.state = self.info_return.clone(),
+            _ => self.needs_redraw = false,
+        }
+        true
+    }
+
     fn handle_help_key(&mut self, key: char) -> bool {
         match key {
             'q' | 'Q' | KEY_ENTER | KEY_BACKSPACE => self.state = AppState::MainMenu,
+            KEY_UP => self.help_scroll = self.help_scroll.saturating_sub(self.repeat_step()),
+            KEY_DOWN => {
+                let last = strings::help_lines(self.settings.language).len().saturating_sub(1);
+                self.help_scroll = (self.help_scroll + self.repeat_step()).min(last);
+            }
             _ => self.needs_redraw = false,
         }
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(name: &str) -> SavedBarcode {
+        SavedBarcode {
+            name: String::from(name),
+            text: String::from("12345"),
+            format: BarcodeFormat::Code128,
+            category: None,
+            created: 0,
+        }
+    }
+
+    fn names(app: &BarcodeApp) -> Vec<&str> {
+        app.saved_codes.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn undo_delete_keeps_later_saves() {
+        let mut app = BarcodeApp::new();
+        app.saved_codes = vec![code("A"), code("C")];
+        app.state = AppState::LoadList;
+        app.handle_key('d');
+        assert_eq!(names(&app), ["C"]);
+
+        app.save_name = String::from("B");
+        app.barcode_text = String::from("HELLO");
+        app.save_shown();
+        app.state = AppState::LoadList;
+        app.handle_key('u');
+        assert_eq!(names(&app), ["A", "C", "B"]);
+    }
+
+    #[test]
+    fn latin1_input_fills_to_the_character_limit() {
+        let mut app = BarcodeApp::new();
+        app.settings.code128_latin1 = true;
+        app.settings.prefix = String::from("é");
+        app.state = AppState::Input;
+        let max = app.max_input_len();
+        assert_eq!(max, MAX_CODE128_LEN - 1);
+        for _ in 0..max + 1 {
+            app.handle_key('é');
+        }
+        assert_eq!(app.input_text.chars().count(), max);
+    }
+
+    #[test]
+    fn too_wide_follows_the_canvas_width() {
+        let too_wide = |width| {
+            let mut app = BarcodeApp::new();
+            app.canvas_width.set(width);
+            app.state = AppState::Input;
+            app.input_text = String::from("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123");
+            app.handle_key(KEY_ENTER);
+            assert_eq!(app.state, AppState::Display);
+            app.status_message.as_deref() == Some(app.tr(Str::TooWide))
+        };
+        assert!(too_wide(DEFAULT_CANVAS_WIDTH));
+        assert!(!too_wide(600));
+    }
+
+    #[test]
+    fn undo_purge_restores_positions() {
+        let mut app = BarcodeApp::new();
+        let auto = |name| SavedBarcode { category: Some(String::from(AUTOSAVE_CATEGORY)), ..code(name) };
+        app.saved_codes = vec![auto("x1"), code("A"), auto("x2"), code("B")];
+        app.state = AppState::LoadList;
+        app.handle_key('p');
+        assert_eq!(names(&app), ["A", "B"]);
+        app.handle_key('u');
+        assert_eq!(names(&app), ["x1", "A", "x2", "B"]);
+    }
+}
@@ -1,4 +1,5 @@
-//! Barcode encoder — Code 128, Code 39, EAN-13, UPC-A.
+//! Barcode encoder — Code 128, Code 39, EAN-13, UPC-A, Telepen, Standard 2 of 5,
+//! Code 32, PZN.
 //!
 //! Zero external dependencies. Pure Rust. Built for Precursor.
 //! Follows the encoding agent pattern from the QR Code Generator.
@@ -16,6 +17,11 @@ pub enum BarcodeFormat {
     Code39,
     Ean13,
     UpcA,
+    Telepen,
+    Std2of5,
+    Matrix2of5,
+    Code32,
+    Pzn,
 }
 
 impl BarcodeFormat {
@@ -25,6 +31,11 @@ impl BarcodeFormat {
             BarcodeFormat::Code39 => "Code 39",
             BarcodeFormat::Ean13 => "EAN-13",
             BarcodeFormat::UpcA => "UPC-A",
+            BarcodeFormat::Telepen => "Telepen",
+            BarcodeFormat::Std2of5 => "Std 2 of 5",
+            BarcodeFormat::Matrix2of5 => "Matrix 2 of 5",
+            BarcodeFormat::Code32 => "Code 32",
+            BarcodeFormat::Pzn => "PZN",
         }
     }
 
@@ -34,6 +45,11 @@ impl BarcodeFormat {
             BarcodeFormat::Code39 => "C39",
             BarcodeFormat::Ean13 => "EAN13",
             BarcodeFormat::UpcA => "UPCA",
+            BarcodeFormat::Telepen => "TELE",
+            BarcodeFormat::Std2of5 => "STD25",
+            BarcodeFormat::Matrix2of5 => "MAT25",
+            BarcodeFormat::Code32 => "C32",
+            BarcodeFormat::Pzn => "PZN",
         }
     }
 
@@ -43,26 +59,177 @@ impl BarcodeFormat {
             BarcodeFormat::Code39,
             BarcodeFormat::Ean13,
             BarcodeFormat::UpcA,
+            BarcodeFormat::Telepen,
+            BarcodeFormat::Std2of5,
+            BarcodeFormat::Matrix2of5,
+            BarcodeFormat::Code32,
+            BarcodeFormat::Pzn,
         ]
     }
 
+    /// What the format accepts, for the Format Info screen. Keep in step
+    /// with `is_valid` and the encoders' length checks.
+    pub fn constraints(&self) -> Constraints {
+        match self {
+            BarcodeFormat::Code128 => Constraints {
+                chars: "ASCII 0-126, Latin-1 with the setting on",
+                min_len: 1,
+                max_len: None,
+                check: "mod 103, added automatically",
+                example: "Hello, World!",
+            },
+            BarcodeFormat::Code39 => Constraints {
+                chars: "A-Z 0-9 space - . $ / + %",
+                min_len: 1,
+                max_len: None,
+                check: "none",
+                example: "CODE-39",
+            },
+            BarcodeFormat::Ean13 => Constraints {
+                chars: "0-9, or an ISBN-10",
+                min_len: 12,
+                max_len: Some(13),
+                check: "mod 10, weights 1/3; typed one per Check Digit setting",
+                example: "400638133393",
+            },
+            BarcodeFormat::UpcA => Constraints {
+                chars: "0-9",
+                min_len: 11,
+                max_len: Some(12),
+                check: "mod 10, weights 3/1; typed one per Check Digit setting",
+                example: "03600029145",
+            },
+            BarcodeFormat::Telepen => Constraints {
+                chars: "ASCII 0-127",
+                min_len: 1,
+                max_len: None,
+                check: "mod 127, added automatically",
+                example: "Telepen",
+            },
+            BarcodeFormat::Std2of5 => Constraints {
+                chars: "0-9",
+                min_len: 1,
+                max_len: None,
+                check: "optional mod 10 (2 of 5 Check setting)",
+                example: "1234567",
+            },
+            BarcodeFormat::Matrix2of5 => Constraints {
+                chars: "0-9",
+                min_len: 1,
+                max_len: None,
+                check: "optional mod 10 (2 of 5 Check setting)",
+                example: "1234567",
+            },
+            BarcodeFormat::Code32 => Constraints {
+                chars: "0-9",
+                min_len: 8,
+                max_len: Some(9),
+                check: "mod 10, doubling; 9th digit corrected",
+                example: "01234567",
+            },
+            BarcodeFormat::Pzn => Constraints {
+                chars: "0-9",
+                min_len: 7,
+                max_len: Some(7),
+                check: "mod 11, weights 1-7, added automatically",
+                example: "0123456",
+            },
+        }
+    }
+
+    /// Starting `(bar_width, bar_height)` in pixels for this format. EAN and
+    /// UPC get wider modules and retail proportions; the long 2 of 5 and
+    /// short pharmacy codes get less height.
+    pub fn default_bar_size(&self) -> (u8, u16) {
+        match self {
+            BarcodeFormat::Ean13 | BarcodeFormat::UpcA => (3, 200),
+            BarcodeFormat::Code128 | BarcodeFormat::Code39 | BarcodeFormat::Telepen => (2, 160),
+            BarcodeFormat::Code32 | BarcodeFormat::Pzn | BarcodeFormat::Matrix2of5 => (2, 120),
+            BarcodeFormat::Std2of5 => (1, 120),
+        }
+    }
+
+    /// Formats whose payload is digits only.
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            BarcodeFormat::Ean13
+                | BarcodeFormat::UpcA
+                | BarcodeFormat::Std2of5
+                | BarcodeFormat::Matrix2of5
+                | BarcodeFormat::Code32
+                | BarcodeFormat::Pzn
+        )
+    }
+
     pub fn next(&self) -> BarcodeFormat {
         match self {
             BarcodeFormat::Code128 => BarcodeFormat::Code39,
             BarcodeFormat::Code39 => BarcodeFormat::Ean13,
             BarcodeFormat::Ean13 => BarcodeFormat::UpcA,
-            BarcodeFormat::UpcA => BarcodeFormat::Code128,
+            BarcodeFormat::UpcA => BarcodeFormat::Telepen,
+            BarcodeFormat::Telepen => BarcodeFormat::Std2of5,
+            BarcodeFormat::Std2of5 => BarcodeFormat::Matrix2of5,
+            BarcodeFormat::Matrix2of5 => BarcodeFormat::Code32,
+            BarcodeFormat::Code32 => BarcodeFormat::Pzn,
+            BarcodeFormat::Pzn => BarcodeFormat::Code128,
         }
     }
 }
 
+/// Input rules for one format, see `BarcodeFormat::constraints`.
+#[derive(Debug, Clone, Copy)]
+pub struct Constraints {
+    /// Accepted characters, described for people.
+    pub chars: &'static str,
+    pub min_len: usize,
+    /// `None` when only the symbol width limits the length.
+    pub max_len: Option<usize>,
+    /// How the check digit works, if there is one.
+    pub check: &'static str,
+    /// An input that encodes.
+    pub example: &'static str,
+}
+
 /// Auto-detect the best format for given text.
 pub fn auto_detect(text: &str) -> BarcodeFormat {
+    best_format(text)
+}
+
+/// Formats after the best one to offer as alternatives, most useful first.
+const ALTERNATIVES: [BarcodeFormat; 9] = [
+    BarcodeFormat::Ean13,
+    BarcodeFormat::UpcA,
+    BarcodeFormat::Code128,
+    BarcodeFormat::Code39,
+    BarcodeFormat::Telepen,
+    BarcodeFormat::Code32,
+    BarcodeFormat::Pzn,
+    BarcodeFormat::Std2of5,
+    BarcodeFormat::Matrix2of5,
+];
+
+/// Every format that takes `text` as typed, in preference order. The first
+/// is always what `auto_detect` picks, even if it can't encode the text.
+pub fn auto_detect_ranked(text: &str) -> Vec<BarcodeFormat> {
+    let mut ranked = Vec::new();
+    ranked.push(best_format(text));
+    for &format in ALTERNATIVES.iter() {
+        if !ranked.contains(&format) && is_valid(text, format) && encode(text, format).is_ok() {
+            ranked.push(format);
+        }
+    }
+    ranked
+}
+
+fn best_format(text: &str) -> BarcodeFormat {
     let all_digits = text.chars().all(|c| c.is_ascii_digit());
     if all_digits && text.len() == 13 {
         BarcodeFormat::Ean13
     } else if all_digits && text.len() == 12 {
         BarcodeFormat::UpcA
+    } else if is_isbn10(text) && isbn10_to_ean13(text).is_ok() {
+        BarcodeFormat::Ean13
     } else if text
         .chars()
         .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || " -.$/+%".contains(c))
@@ -73,42 +240,427 @@ pub fn auto_detect(text: &str) -> BarcodeFormat {
     }
 }
 
-/// Result of encoding: a list of bar widths (alternating black/white starting with black).
+// ─── Module Stream ──────────────────────────────────────────────────────────
+
+/// Packed module stream: one bit per module, MSB-first within each byte.
+/// A set bit is a dark bar, a clear bit is a light space.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleBits {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl ModuleBits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Module at index `i`. Out-of-range indices read as light.
+    pub fn get(&self, i: usize) -> bool {
+        if i >= self.len {
+            return false;
+        }
+        self.bytes[i / 8] & (0x80 >> (i % 8)) != 0
+    }
+
+    pub fn push(&mut self, dark: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if dark {
+            let last = self.bytes.len() - 1;
+            self.bytes[last] |= 0x80 >> (self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    pub fn extend_from_slice(&mut self, modules: &[bool]) {
+        for &dark in modules {
+            self.push(dark);
+        }
+    }
+
+    pub fn iter(&self) -> ModuleIter<'_> {
+        ModuleIter { bits: self, pos: 0 }
+    }
+
+    /// Unpack into one bool per module.
+    pub fn to_vec(&self) -> Vec<bool> {
+        self.iter().collect()
+    }
+
+    /// Run-length view of the stream: (dark, width) for each bar or space.
+    pub fn runs(&self) -> Vec<(bool, usize)> {
+        let mut runs: Vec<(bool, usize)> = Vec::new();
+        for dark in self.iter() {
+            match runs.last_mut() {
+                Some(last) if last.0 == dark => last.1 += 1,
+                _ => runs.push((dark, 1)),
+            }
+        }
+        runs
+    }
+}
+
+impl Extend<bool> for ModuleBits {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        for dark in iter {
+            self.push(dark);
+        }
+    }
+}
+
+impl FromIterator<bool> for ModuleBits {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = ModuleBits::new();
+        bits.extend(iter);
+        bits
+    }
+}
+
+impl<'a> IntoIterator for &'a ModuleBits {
+    type Item = bool;
+    type IntoIter = ModuleIter<'a>;
+
+    fn into_iter(self) -> ModuleIter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over a `ModuleBits` stream, yielding `true` for dark modules.
+pub struct ModuleIter<'a> {
+    bits: &'a ModuleBits,
+    pos: usize,
+}
+
+impl Iterator for ModuleIter<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if self.pos >= self.bits.len {
+            return None;
+        }
+        let dark = self.bits.get(self.pos);
+        self.pos += 1;
+        Some(dark)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.bits.len - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for ModuleIter<'_> {}
+
+/// Result of encoding: the module pattern and the text printed under it.
 #[derive(Debug, Clone)]
 pub struct Barcode {
-    /// Module pattern: true = dark bar, false = light space.
-    pub modules: Vec<bool>,
+    /// Module pattern: set = dark bar, clear = light space.
+    pub modules: ModuleBits,
     /// Human-readable text to display below.
     pub text: String,
     /// Format used.
     pub format: BarcodeFormat,
 }
 
-/// Encode text into a barcode. Returns None if the text is invalid for the format.
-pub fn encode(text: &str, format: BarcodeFormat) -> Option<Barcode> {
+/// Text rows drawn by `Barcode::to_ascii`.
+const ASCII_ROWS: usize = 2;
+
+impl Barcode {
+    /// Render as `#`/space text rows, each module repeated `bar_width` times,
+    /// followed by the human-readable text. For debugging without the GAM.
+    pub fn to_ascii(&self, bar_width: usize) -> String {
+        let mut row = String::new();
+        for dark in self.modules.iter() {
+            for _ in 0..bar_width {
+                row.push(if dark { '#' } else { ' ' });
+            }
+        }
+        let mut out = String::new();
+        for _ in 0..ASCII_ROWS {
+            out.push_str(&row);
+            out.push('\n');
+        }
+        out.push_str(&self.text);
+        out.push('\n');
+        out
+    }
+
+    /// AIM symbology identifier that a scanner prefixes to this symbol's
+    /// data: `]C1` for GS1-128 (Code 128 opening with FNC1), `]C0` for
+    /// other Code 128, `]A0` for Code 39 without a check character (Code 32
+    /// and PZN included, as they ride on Code 39), `]E0` for EAN-13 and
+    /// UPC-A (UPC-A reads as an EAN-13 with a leading zero), `]B0` for full
+    /// ASCII Telepen, `]S0` for Standard 2 of 5, and `]X0` (no assigned
+    /// symbology) for Matrix 2 of 5. Code 39 with a check digit or Full
+    /// ASCII (`]A1`-`]A7`) isn't produced by these encoders.
+    pub fn aim_identifier(&self) -> &'static str {
+        match self.format {
+            BarcodeFormat::Code128 if self.code128_starts_with_fnc1() => "]C1",
+            BarcodeFormat::Code128 => "]C0",
+            BarcodeFormat::Code39 | BarcodeFormat::Code32 | BarcodeFormat::Pzn => "]A0",
+            BarcodeFormat::Ean13 | BarcodeFormat::UpcA => "]E0",
+            BarcodeFormat::Telepen => "]B0",
+            BarcodeFormat::Std2of5 => "]S0",
+            BarcodeFormat::Matrix2of5 => "]X0",
+        }
+    }
+
+    /// Whether the symbol after the Code 128 start code is FNC1.
+    fn code128_starts_with_fnc1(&self) -> bool {
+        let first = match self.modules.iter().position(|dark| dark) {
+            Some(first) => first,
+            None => return false,
+        };
+        let fnc1 = pattern_to_modules(&CODE128_PATTERNS[FNC1]);
+        fnc1.iter().enumerate().all(|(j, &dark)| self.modules.get(first + 11 + j) == dark)
+    }
+
+    /// Decode `modules` and compare with `text`, to check the symbol reads
+    /// back. `None` for formats without a decoder.
+    pub fn verify(&self) -> Option<bool> {
+        let decoded = match self.format {
+            BarcodeFormat::Code128 => decode_code128(&self.modules),
+            BarcodeFormat::Code39 => decode_code39(&self.modules),
+            BarcodeFormat::Code32 => decode_code39(&self.modules).and_then(|s| code32_from_base32(&s)),
+            BarcodeFormat::Pzn => decode_code39(&self.modules)
+                .and_then(|s| s.strip_prefix('-').map(|n| alloc::format!("PZN - {}", n))),
+            BarcodeFormat::Ean13
+            | BarcodeFormat::UpcA
+            | BarcodeFormat::Telepen
+            | BarcodeFormat::Std2of5
+            | BarcodeFormat::Matrix2of5 => return None,
+        };
+        Some(decoded.as_deref() == Some(self.text.as_str()))
+    }
+}
+
+/// Why a text couldn't be encoded in the requested format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EncodeError {
+    /// Nothing to encode.
+    Empty,
+    /// A character the format can't represent.
+    InvalidChar(char),
+    /// Too few or too many characters for the format.
+    WrongLength { got: usize, min: usize, max: usize },
+    /// The number has no valid check digit (a PZN whose mod 11 comes to 10).
+    NoCheckDigit,
+    /// The entered check digit doesn't match the one computed from the data.
+    BadCheckDigit { expected: u8, got: u8 },
+    /// An ISBN-10 whose check character (which may be `X`) doesn't match.
+    BadIsbnCheck { expected: char, got: char },
+}
+
+impl core::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EncodeError::Empty => write!(f, "nothing to encode"),
+            EncodeError::InvalidChar(c) => write!(f, "'{}' not allowed", c.escape_default()),
+            EncodeError::WrongLength { got, min, max } if min == max => {
+                write!(f, "needs {} chars, got {}", min, got)
+            }
+            EncodeError::WrongLength { got, min, max } => {
+                write!(f, "needs {}-{} chars, got {}", min, max, got)
+            }
+            EncodeError::NoCheckDigit => write!(f, "no valid check digit"),
+            EncodeError::BadCheckDigit { expected, got } => {
+                write!(f, "check digit should be {}, not {}", expected, got)
+            }
+            EncodeError::BadIsbnCheck { expected, got } => {
+                write!(f, "ISBN check should be {}, not {}", expected, got)
+            }
+        }
+    }
+}
+
+/// Wide-to-narrow element ratio used by `encode`.
+pub const DEFAULT_WIDE_RATIO: u8 = 3;
+/// Code 39 inter-character gap used by `encode`, in narrow widths.
+pub const DEFAULT_CODE39_GAP: u8 = 1;
+
+/// What to do with a check digit typed as part of a full-length EAN-13,
+/// UPC-A or Code 32.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckMode {
+    /// Replace it with the computed one.
+    Recompute,
+    /// Reject the input if it doesn't match.
+    Require,
+    /// Keep it as typed; only compute one when it is missing.
+    Append,
+}
+
+/// Encoder knobs. Element sizing applies to the two-width symbologies
+/// (Code 39, Code 32, PZN, Standard 2 of 5), the check mode to EAN-13,
+/// UPC-A and Code 32; other formats ignore them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EncodeOptions {
+    /// Wide element width in narrow widths, 2 or 3.
+    pub wide_ratio: u8,
+    /// Code 39 (and Code 32/PZN) gap between characters in narrow widths, 1-3.
+    pub code39_gap: u8,
+    pub check_mode: CheckMode,
+    /// Code 128: never pack digit pairs into subset C, for readers that want
+    /// subset B. Control characters still go through subset A.
+    pub code128_force_b: bool,
+    /// Code 128: take characters 128-255 (Latin-1), each encoded as FNC4
+    /// and the character 128 below it.
+    pub code128_latin1: bool,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            wide_ratio: DEFAULT_WIDE_RATIO,
+            code39_gap: DEFAULT_CODE39_GAP,
+            check_mode: CheckMode::Recompute,
+            code128_force_b: false,
+            code128_latin1: false,
+        }
+    }
+}
+
+/// Encode text into a barcode. Returns an error if the text is invalid for the format.
+pub fn encode(text: &str, format: BarcodeFormat) -> Result<Barcode, EncodeError> {
+    encode_with(text, format, EncodeOptions::default())
+}
+
+/// Like `encode`, with the given element sizing.
+pub fn encode_with(text: &str, format: BarcodeFormat, options: EncodeOptions) -> Result<Barcode, EncodeError> {
     if text.is_empty() {
-        return None;
+        return Err(EncodeError::Empty);
     }
+    let wide = options.wide_ratio.clamp(2, 3);
+    let gap = options.code39_gap.clamp(1, 3);
     match format {
-        BarcodeFormat::Code128 => encode_code128(text),
-        BarcodeFormat::Code39 => encode_code39(text),
-        BarcodeFormat::Ean13 => encode_ean13(text),
-        BarcodeFormat::UpcA => encode_upc_a(text),
+        BarcodeFormat::Code128 => encode_code128(text, options.code128_force_b, options.code128_latin1),
+        BarcodeFormat::Code39 => encode_code39(text, wide, gap),
+        BarcodeFormat::Ean13 => encode_ean13(text, options.check_mode),
+        BarcodeFormat::UpcA => encode_upc_a(text, options.check_mode),
+        BarcodeFormat::Telepen => encode_telepen(text),
+        BarcodeFormat::Std2of5 => encode_std2of5(text, wide),
+        BarcodeFormat::Matrix2of5 => encode_matrix2of5(text, wide),
+        BarcodeFormat::Code32 => encode_code32(text, options.check_mode, wide, gap),
+        BarcodeFormat::Pzn => encode_pzn(text, wide, gap),
+    }
+}
+
+/// For a full-length EAN-13, UPC-A or Code 32, check the last digit against
+/// the one computed from the data, whatever `CheckMode` the encoder would use.
+/// Returns `Ok(false)` when there was no check digit to compare (shorter
+/// input, other formats).
+pub fn verify_check_digit(text: &str, format: BarcodeFormat) -> Result<bool, EncodeError> {
+    let full_len = match format {
+        BarcodeFormat::Ean13 => 13,
+        BarcodeFormat::UpcA => 12,
+        BarcodeFormat::Code32 => 9,
+        _ => return Ok(false),
+    };
+    if text.len() != full_len || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return Ok(false);
+    }
+    let digits: Vec<u8> = text.bytes().map(|b| b - b'0').collect();
+    let (data, got) = (&digits[..full_len - 1], digits[full_len - 1]);
+    let expected = match format {
+        BarcodeFormat::Ean13 => ean13_check_digit(data),
+        BarcodeFormat::Code32 => code32_check_digit(data),
+        _ => upc_check_digit(data),
+    };
+    if expected == got {
+        Ok(true)
+    } else {
+        Err(EncodeError::BadCheckDigit { expected, got })
     }
 }
 
+/// `text` with everything but ASCII digits removed, for the Strip
+/// Non-Digits setting on the `is_numeric` formats.
+pub fn digits_only(text: &str) -> String {
+    text.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
 /// Check if text is valid for the given format.
 pub fn is_valid(text: &str, format: BarcodeFormat) -> bool {
+    if first_invalid_char(text, format).is_some() {
+        return false;
+    }
     match format {
-        BarcodeFormat::Code128 => text.chars().all(|c| (c as u32) < 128),
-        BarcodeFormat::Code39 => text
-            .chars()
-            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || " -.$/+%".contains(c)),
-        BarcodeFormat::Ean13 => text.len() <= 13 && text.chars().all(|c| c.is_ascii_digit()),
-        BarcodeFormat::UpcA => text.len() <= 12 && text.chars().all(|c| c.is_ascii_digit()),
+        BarcodeFormat::Pzn => {
+            let digits: Vec<u8> = text.bytes().map(|b| b - b'0').collect();
+            digits.len() < 7 || pzn_check_digit(&digits).is_some()
+        }
+        _ => true,
+    }
+}
+
+/// Random payload `format` accepts, for exercising the encoders: data digits
+/// only for EAN/UPC/Code 32, the printable part of the character set for the
+/// rest. Check digits are left to the encoder. `seed` is a xorshift32 state
+/// (must not be 0) and is advanced, so the same seed gives the same sequence.
+pub fn sample_payload(format: BarcodeFormat, seed: &mut u32) -> String {
+    let mut next = |n: u32| {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 17;
+        *seed ^= *seed << 5;
+        *seed % n
+    };
+    loop {
+        let len = match format {
+            BarcodeFormat::Ean13 => 12,
+            BarcodeFormat::UpcA => 11,
+            BarcodeFormat::Code32 => 8,
+            BarcodeFormat::Pzn => 7,
+            _ => 6 + next(7) as usize,
+        };
+        let text: String = (0..len)
+            .map(|_| match format {
+                BarcodeFormat::Code128 | BarcodeFormat::Telepen => (b' ' + next(95) as u8) as char,
+                // Without the '*' start/stop character
+                BarcodeFormat::Code39 => CODE39_CHARS[next(43) as usize] as char,
+                _ => (b'0' + next(10) as u8) as char,
+            })
+            .collect();
+        // Only PZN has data that can't take a check digit
+        if is_valid(&text, format) {
+            return text;
+        }
     }
 }
 
+/// Index, in chars, of the first character `format` can't take, or of the
+/// first one past its maximum length.
+pub fn first_invalid_char(text: &str, format: BarcodeFormat) -> Option<usize> {
+    if format == BarcodeFormat::Ean13 && is_isbn10(text) {
+        return None;
+    }
+    let allowed = |c: char| match format {
+        BarcodeFormat::Code128 => (c as u32) <= 126,
+        BarcodeFormat::Code39 => c.is_ascii_uppercase() || c.is_ascii_digit() || " -.$/+%".contains(c),
+        BarcodeFormat::Telepen => c.is_ascii(),
+        _ => c.is_ascii_digit(),
+    };
+    let max_len = match format {
+        BarcodeFormat::Ean13 => 13,
+        BarcodeFormat::UpcA => 12,
+        BarcodeFormat::Code32 => 9,
+        BarcodeFormat::Pzn => 7,
+        _ => usize::MAX,
+    };
+    text.chars()
+        .position(|c| !allowed(c))
+        .or_else(|| (text.chars().count() > max_len).then_some(max_len))
+}
+
 // ─── Code 128 ───────────────────────────────────────────────────────────────
 
 /// Code 128 bar patterns: each symbol is 6 alternating bar/space widths summing to 11 modules.
@@ -150,9 +702,9 @@ const CODE128_PATTERNS: [[u8; 6]; 107] = [
     [1,1,1,3,2,3], // 33
     [1,3,1,1,2,3], // 34
     [1,3,1,3,2,1], // 35
-    [1,1,2,3,2,2], // 36 (not used below but part of pattern set)
-    [1,3,2,1,2,2], // 37
-    [1,3,2,3,2,0], // 38 — special; actual: [1,3,2,3,2,0] placeholder
+    [1,1,2,3,1,3], // 36
+    [1,3,2,1,1,3], // 37
+    [1,3,2,3,1,1], // 38
     [2,1,1,3,1,3], // 39
     [2,3,1,1,1,3], // 40
     [2,3,1,3,1,1], // 41
@@ -174,71 +726,277 @@ const CODE128_PATTERNS: [[u8; 6]; 107] = [
     [3,1,2,1,1,3], // 57
     [3,1,2,3,1,1], // 58
     [3,3,2,1,1,1], // 59
-    [2,1,1,2,1,3], // 60 (not used directly below)
-    [2,1,1,2,3,1], // 61
-    [2,3,1,2,1,1], // 62 (not used directly below)
-    [1,1,2,2,1,3], // 63 (space in subset B = value 0)
-    [1,1,2,2,3,1], // 64
-    [1,3,2,2,1,1], // 65 (not used directly below)
-    [1,1,1,1,3,3], // 66 (not used directly below)
-    [1,3,1,1,3,1], // 67 (not used directly below)
-    [1,1,3,1,1,3], // 68 (not used directly below)
-    [1,1,3,3,1,1], // 69 (not used directly below)
-    [1,3,3,1,1,1], // 70 (not used directly below)
-    [3,1,1,1,3,1], // 71 (not used directly below)
-    [3,1,3,1,1,1], // 72 (not used directly below)
-    [2,1,1,1,3,3], // 73 (not used directly below)
-    [2,1,3,1,3,0], // 74 — placeholder
-    [3,1,1,2,1,2], // 75 (not used directly below)
-    [3,1,1,2,2,1], // 76 (not used directly below) (not used below)
-    [1,2,1,1,2,3], // 77
-    [1,2,1,3,2,1], // 78 (not used directly below) (not used below)
-    [1,2,1,1,3,2], // 79 (not used directly below) (not used below)
-    [3,2,1,1,1,2], // 80 (not used directly below) (not used below)
-    [1,1,1,2,2,3], // 81 (not used directly below) (not used below)
-    [1,1,1,2,3,2], // 82 (not used directly below) (not used below)
-    [1,2,1,2,3,1], // 83 (not used directly below)
-    [1,2,3,2,1,1], // 84 (not used directly below)
-    [3,2,1,2,1,1], // 85 (not used directly below)
-    [2,1,2,2,1,2], // 86 (not used directly below)
-    [1,1,2,1,2,3], // 87 (not used directly below) — duplicate of earlier
-    [1,2,2,2,1,2], // 88 (not used directly below)
-    [2,2,1,2,2,1], // 89 (not used directly below)
-    [2,1,1,1,2,3], // 90 (not used directly below)
-    [2,1,1,2,2,2], // 91 (not used directly below)
-    [2,2,1,1,2,2], // 92 (not used directly below)
-    [2,2,2,1,1,2], // 93 (not used directly below)
-    [2,2,2,2,1,1], // 94 (not used directly below)
-    [1,1,3,2,2,1], // 95 (not used directly below)
-    [1,1,2,2,2,2], // 96 (not used directly below)
-    [2,2,2,1,2,1], // 97 (not used directly below)
-    [2,1,1,2,2,2], // 98 (not used directly below) SHIFT (not used directly)
-    [3,1,2,2,2,1], // 99  CODE_C
-    [2,1,2,1,1,3], // 100 CODE_B (FNC4 in B)
-    [2,1,2,3,1,1], // 101 CODE_A (FNC4 in A)
-    [1,2,1,1,2,3], // 102 FNC1
-    [1,2,3,2,1,1], // 103 START_A (not used directly — we use numeric values)
-    [1,2,1,1,3,2], // 104 START_B
-    [1,1,3,1,2,3], // 105 START_C
-    [2,3,3,1,1,1], // 106 STOP (13 modules including final bar)
+    [3,1,4,1,1,1], // 60
+    [2,2,1,4,1,1], // 61
+    [4,3,1,1,1,1], // 62
+    [1,1,1,2,2,4], // 63
+    [1,1,1,4,2,2], // 64
+    [1,2,1,1,2,4], // 65
+    [1,2,1,4,2,1], // 66
+    [1,4,1,1,2,2], // 67
+    [1,4,1,2,2,1], // 68
+    [1,1,2,2,1,4], // 69
+    [1,1,2,4,1,2], // 70
+    [1,2,2,1,1,4], // 71
+    [1,2,2,4,1,1], // 72
+    [1,4,2,1,1,2], // 73
+    [1,4,2,2,1,1], // 74
+    [2,4,1,2,1,1], // 75
+    [2,2,1,1,1,4], // 76
+    [4,1,3,1,1,1], // 77
+    [2,4,1,1,1,2], // 78
+    [1,3,4,1,1,1], // 79
+    [1,1,1,2,4,2], // 80
+    [1,2,1,1,4,2], // 81
+    [1,2,1,2,4,1], // 82
+    [1,1,4,2,1,2], // 83
+    [1,2,4,1,1,2], // 84
+    [1,2,4,2,1,1], // 85
+    [4,1,1,2,1,2], // 86
+    [4,2,1,1,1,2], // 87
+    [4,2,1,2,1,1], // 88
+    [2,1,2,1,4,1], // 89
+    [2,1,4,1,2,1], // 90
+    [4,1,2,1,2,1], // 91
+    [1,1,1,1,4,3], // 92
+    [1,1,1,3,4,1], // 93
+    [1,3,1,1,4,1], // 94
+    [1,1,4,1,1,3], // 95
+    [1,1,4,3,1,1], // 96 FNC3 (A/B)
+    [4,1,1,1,1,3], // 97 FNC2 (A/B)
+    [4,1,1,3,1,1], // 98 SHIFT (A/B)
+    [1,1,3,1,4,1], // 99 CODE_C
+    [1,1,4,1,3,1], // 100 CODE_B (FNC4 in B)
+    [3,1,1,1,4,1], // 101 CODE_A (FNC4 in A)
+    [4,1,1,1,3,1], // 102 FNC1
+    [2,1,1,4,1,2], // 103 START_A
+    [2,1,1,2,1,4], // 104 START_B
+    [2,1,1,2,3,2], // 105 START_C
+    [2,3,3,1,1,1], // 106 STOP (final 2-module bar appended separately)
 ];
 
 // Code 128 special values
+const START_A: usize = 103;
 const START_B: usize = 104;
 const START_C: usize = 105;
+const CODE_A: usize = 101;
 const CODE_B: usize = 100;
 const CODE_C: usize = 99;
 const STOP: usize = 106;
+const FNC1: usize = 102;
+/// Value of '0' in subset B; the other digits follow it.
+const DIGIT_0_B: usize = 16;
+
+/// Escapes for the Code 128 function characters in the input, with each
+/// one's symbol value in subsets A and B. Only FNC1 exists in subset C.
+const CODE128_FNC_ESCAPES: [(&str, usize); 3] = [
+    ("{FNC1}", FNC1),
+    ("{FNC2}", 97),
+    ("{FNC3}", 96),
+];
+
+/// FNC4 escape: shifts the ASCII character after it up by 128, so it reads
+/// as that Latin-1 character.
+const FNC4_ESCAPE: &str = "{FNC4}";
+
+/// One unit of Code 128 input: a character, or a function character
+/// (an index into `CODE128_FNC_ESCAPES`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Code128Item {
+    Char(char),
+    Fnc(usize),
+}
+
+impl Code128Item {
+    fn is_digit(self) -> bool {
+        matches!(self, Code128Item::Char(c) if c.is_ascii_digit())
+    }
+
+    /// A control character, or a Latin-1 one that is a control character
+    /// less 128: either needs subset A.
+    fn is_control(self) -> bool {
+        matches!(self.base(), Some(c) if (c as u32) < 32)
+    }
+
+    /// Latin-1 characters 128-255, which go out as FNC4 and `base`.
+    fn is_extended(self) -> bool {
+        matches!(self, Code128Item::Char(c) if (c as u32) >= 128)
+    }
+
+    /// The ASCII character encoded for this item: the character itself, or
+    /// for Latin-1, the character 128 below it.
+    fn base(self) -> Option<char> {
+        match self {
+            Code128Item::Char(c) if (c as u32) >= 128 => char::from_u32(c as u32 - 128),
+            Code128Item::Char(c) => Some(c),
+            Code128Item::Fnc(_) => None,
+        }
+    }
+}
+
+/// Split Code 128 input into characters and function characters, and the
+/// human-readable text: the input with the FNC1-3 escapes taken out.
+/// `{FNC4}` and the ASCII character after it become that character plus
+/// 128, the same as typing it in Latin-1; with no such character after it,
+/// the escape is rejected.
+fn code128_items(text: &str) -> Result<(Vec<Code128Item>, String), EncodeError> {
+    let mut items = Vec::new();
+    let mut readable = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some(n) = CODE128_FNC_ESCAPES.iter().position(|(escape, _)| rest.starts_with(escape)) {
+            items.push(Code128Item::Fnc(n));
+            rest = &rest[CODE128_FNC_ESCAPES[n].0.len()..];
+        } else if let Some(after) = rest.strip_prefix(FNC4_ESCAPE) {
+            let escape_next = after.starts_with(FNC4_ESCAPE)
+                || CODE128_FNC_ESCAPES.iter().any(|(escape, _)| after.starts_with(escape));
+            let shifted = after
+                .chars()
+                .next()
+                .filter(|&c| c.is_ascii() && !escape_next)
+                .and_then(|c| char::from_u32(c as u32 + 128))
+                .ok_or(EncodeError::InvalidChar('{'))?;
+            items.push(Code128Item::Char(shifted));
+            readable.push(shifted);
+            rest = &after[1..];
+        } else {
+            items.push(Code128Item::Char(c));
+            readable.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Ok((items, readable))
+}
+
+/// Stop pattern is special: 2,3,3,1,1,1,2 (13 modules including the final bar)
+const CODE128_STOP: [bool; 13] = [
+    true, true, false, false, false, true, true, true, false, true, false, true, true,
+];
+
+/// Subset A: control characters, digits, uppercase and punctuation.
+fn code128_value_a(c: char) -> Option<usize> {
+    let v = c as u32;
+    if v < 32 {
+        Some((v + 64) as usize)
+    } else if v <= 95 {
+        Some((v - 32) as usize)
+    } else {
+        None
+    }
+}
 
+/// Subset B: printable ASCII, and DEL (only reachable as Latin-1 255 less 128).
 fn code128_value_b(c: char) -> Option<usize> {
     let v = c as u32;
-    if v >= 32 && v <= 126 {
+    if (32..=127).contains(&v) {
         Some((v - 32) as usize)
     } else {
         None
     }
 }
 
+/// Decode a Code 128 module stream back to its text, for round-trip checks.
+/// Follows subset switches and rejects symbols whose checksum doesn't match.
+/// FNC1-3 carry no text and are skipped. FNC4 adds 128 to the next
+/// character, and two in a row latch that on (or off again) for the
+/// characters that follow, with a single FNC4 then taking one back out.
+pub fn decode_code128(modules: &ModuleBits) -> Option<String> {
+    let bits = modules.to_vec();
+    let first = bits.iter().position(|&d| d)?;
+    let last = bits.iter().rposition(|&d| d)?;
+    let body = &bits[first..=last];
+
+    // Start + checksum + stop at minimum, every symbol 11 modules wide
+    if body.len() < 11 * 2 + 13 || !(body.len() - 13).is_multiple_of(11) {
+        return None;
+    }
+    let (symbols, stop) = body.split_at(body.len() - 13);
+    if stop != CODE128_STOP {
+        return None;
+    }
+
+    let mut values = Vec::new();
+    for chunk in symbols.chunks(11) {
+        let mut widths = [0u8; 6];
+        let mut elem = 0;
+        for (j, &dark) in chunk.iter().enumerate() {
+            if j > 0 && dark != chunk[j - 1] {
+                elem += 1;
+            }
+            if elem >= 6 || dark != (elem % 2 == 0) {
+                return None;
+            }
+            widths[elem] += 1;
+        }
+        values.push(CODE128_PATTERNS[..STOP].iter().position(|p| *p == widths)?);
+    }
+
+    let (data, check) = values.split_at(values.len() - 1);
+    if code128_checksum(data) != check[0] {
+        return None;
+    }
+
+    let mut set = match data[0] {
+        START_A => 'A',
+        START_B => 'B',
+        START_C => 'C',
+        _ => return None,
+    };
+    let mut text = String::new();
+    let mut latched = false;
+    let mut shifted = false;
+    let data = &data[1..];
+    let mut k = 0;
+    while k < data.len() {
+        let val = data[k];
+        k += 1;
+        let fnc4 = matches!((set, val), ('A', CODE_A) | ('B', CODE_B));
+        if fnc4 {
+            if data.get(k) == Some(&val) {
+                latched = !latched;
+                k += 1;
+            } else {
+                shifted = true;
+            }
+            continue;
+        }
+        let high = if latched != shifted { 128 } else { 0 };
+        let ascii = match (set, val) {
+            ('B', 0..=95) => Some(val as u32 + 32),
+            ('A', 0..=63) => Some(val as u32 + 32),
+            ('A', 64..=95) => Some(val as u32 - 64),
+            _ => None,
+        };
+        if let Some(ascii) = ascii {
+            text.push(char::from_u32(ascii + high)?);
+            shifted = false;
+            continue;
+        }
+        match (set, val) {
+            ('C', 0..=99) => {
+                text.push((b'0' + (val / 10) as u8) as char);
+                text.push((b'0' + (val % 10) as u8) as char);
+            }
+            ('A', 96..=97) | ('B', 96..=97) | (_, FNC1) => {}
+            ('A', CODE_C) | ('B', CODE_C) => set = 'C',
+            ('A', CODE_B) | ('C', CODE_B) => set = 'B',
+            ('B', CODE_A) | ('C', CODE_A) => set = 'A',
+            _ => return None,
+        }
+    }
+    Some(text)
+}
+
+/// Code 128 check value: start value plus each symbol value weighted by its position, mod 103.
+/// `values` starts with the start code and excludes the check and stop symbols.
+pub fn code128_checksum(values: &[usize]) -> usize {
+    let mut checksum = values[0]; // start code
+    for (pos, &val) in values[1..].iter().enumerate() {
+        checksum += val * (pos + 1);
+    }
+    checksum % 103
+}
+
 fn pattern_to_modules(pattern: &[u8; 6]) -> Vec<bool> {
     let mut modules = Vec::new();
     for (i, &width) in pattern.iter().enumerate() {
@@ -250,73 +1008,122 @@ fn pattern_to_modules(pattern: &[u8; 6]) -> Vec<bool> {
     modules
 }
 
-fn encode_code128(text: &str) -> Option<Barcode> {
-    // Validate: all ASCII
-    if !text.chars().all(|c| (c as u32) >= 32 && (c as u32) <= 126) {
-        return None;
+/// With `force_b`, digits are encoded one per symbol in subset B instead of
+/// in pairs in subset C: a wider symbol with the same data. `{FNC1}` to
+/// `{FNC3}` in `text` encode the function characters and are left out of
+/// the barcode's `text`; `{FNC4}` shifts the next character into Latin-1.
+/// With `latin1`, characters 128-255 are taken too, each shifted with its
+/// own FNC4.
+fn encode_code128(text: &str, force_b: bool, latin1: bool) -> Result<Barcode, EncodeError> {
+    // Validate: ASCII up to '~', or all of Latin-1; control characters go
+    // through subset A
+    let max = if latin1 { 255 } else { 126 };
+    if let Some(c) = text.chars().find(|&c| (c as u32) > max) {
+        return Err(EncodeError::InvalidChar(c));
     }
 
-    let chars: Vec<char> = text.chars().collect();
+    let (items, readable) = code128_items(text)?;
     let mut values: Vec<usize> = Vec::new();
     let mut i = 0;
+    let digit = |item: Code128Item| match item {
+        Code128Item::Char(c) => c as usize - '0' as usize,
+        Code128Item::Fnc(_) => 0,
+    };
+    let value_in = |set: char, item: Code128Item| match item.base() {
+        Some(c) if set == 'A' => code128_value_a(c),
+        Some(c) => code128_value_b(c),
+        None => None,
+    };
 
-    // Determine start code: if begins with 4+ digits, start with C
-    let leading_digits = chars.iter().take_while(|c| c.is_ascii_digit()).count();
+    // Determine start code: if begins with 4+ digits, start with C. A
+    // leading FNC1 (GS1-128) is encoded in C too, so look past it.
+    let skip = (items.first() == Some(&Code128Item::Fnc(0))) as usize;
+    let leading_digits = items[skip..].iter().take_while(|it| it.is_digit()).count();
 
-    let (start_code, mut current_set) = if leading_digits >= 4 {
+    let (start_code, mut current_set) = if leading_digits >= 4 && !force_b {
         (START_C, 'C')
+    } else if items[0].is_control() {
+        (START_A, 'A')
     } else {
         (START_B, 'B')
     };
 
     values.push(start_code);
 
-    while i < chars.len() {
-        if current_set == 'C' {
+    while i < items.len() {
+        if let Code128Item::Fnc(n) = items[i] {
+            // FNC2 and FNC3 have no value in subset C
+            let (_, value) = CODE128_FNC_ESCAPES[n];
+            if current_set == 'C' && value != FNC1 {
+                values.push(CODE_B);
+                current_set = 'B';
+            }
+            values.push(value);
+            i += 1;
+        } else if current_set == 'C' {
             // In subset C: encode digit pairs
-            if i + 1 < chars.len()
-                && chars[i].is_ascii_digit()
-                && chars[i + 1].is_ascii_digit()
-            {
-                let val = (chars[i] as usize - '0' as usize) * 10
-                    + (chars[i + 1] as usize - '0' as usize);
-                values.push(val);
+            let run = items[i..].iter().take_while(|it| it.is_digit()).count();
+            if run >= 2 {
+                values.push(digit(items[i]) * 10 + digit(items[i + 1]));
                 i += 2;
             } else {
-                // Switch to B
-                values.push(CODE_B);
-                current_set = 'B';
+                // Switch to B, or A if a control character follows. An odd
+                // digit left over from the run is encoded there directly so
+                // the new subset doesn't reconsider it as a new run.
+                let control_next = items[i + run..].first().is_some_and(|it| it.is_control());
+                if control_next {
+                    values.push(CODE_A);
+                    current_set = 'A';
+                } else {
+                    values.push(CODE_B);
+                    current_set = 'B';
+                }
+                if run == 1 {
+                    values.push(DIGIT_0_B + digit(items[i]));
+                    i += 1;
+                }
             }
         } else {
-            // In subset B
-            // Check if we should switch to C (4+ digits ahead)
-            let remaining_digits = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
-            if remaining_digits >= 4 {
+            // In subset A or B
+            // Switch to C for a digit run worth the switch: 4+ digits ending the
+            // data, or 6+ digits in the middle (which also pay for CODE_B back).
+            let remaining_digits = items[i..].iter().take_while(|it| it.is_digit()).count();
+            let threshold = if i + remaining_digits == items.len() { 4 } else { 6 };
+            if remaining_digits >= threshold && !force_b {
+                // Odd run: take the first digit in B so the rest pairs up in C
+                if remaining_digits % 2 == 1 {
+                    values.push(DIGIT_0_B + digit(items[i]));
+                    i += 1;
+                }
                 values.push(CODE_C);
                 current_set = 'C';
             } else {
-                // Encode single character in subset B
-                if let Some(val) = code128_value_b(chars[i]) {
+                // Encode a single character, or switch between A and B for it
+                let val = value_in(current_set, items[i]);
+                if let Some(val) = val {
+                    if items[i].is_extended() {
+                        values.push(if current_set == 'A' { CODE_A } else { CODE_B });
+                    }
                     values.push(val);
                     i += 1;
+                } else if current_set == 'A' {
+                    values.push(CODE_B);
+                    current_set = 'B';
                 } else {
-                    return None; // invalid character
+                    values.push(CODE_A);
+                    current_set = 'A';
                 }
             }
         }
     }
 
     // Compute checksum
-    let mut checksum = values[0]; // start code
-    for (pos, &val) in values[1..].iter().enumerate() {
-        checksum += val * (pos + 1);
-    }
-    checksum %= 103;
+    let checksum = code128_checksum(&values);
     values.push(checksum);
     values.push(STOP);
 
     // Convert to modules
-    let mut modules = Vec::new();
+    let mut modules = ModuleBits::new();
 
     // Quiet zone (10 modules)
     for _ in 0..10 {
@@ -325,11 +1132,7 @@ fn encode_code128(text: &str) -> Option<Barcode> {
 
     for &val in &values {
         if val == STOP {
-            // Stop pattern is special: 2,3,3,1,1,1,2 (13 modules)
-            let stop_mods: [bool; 13] = [
-                true, true, false, false, false, true, true, true, false, true, true, false, true,
-            ];
-            modules.extend_from_slice(&stop_mods);
+            modules.extend_from_slice(&CODE128_STOP);
         } else if val < 107 {
             modules.extend(pattern_to_modules(&CODE128_PATTERNS[val]));
         }
@@ -340,9 +1143,9 @@ fn encode_code128(text: &str) -> Option<Barcode> {
         modules.push(false);
     }
 
-    Some(Barcode {
+    Ok(Barcode {
         modules,
-        text: String::from(text),
+        text: readable,
         format: BarcodeFormat::Code128,
     })
 }
@@ -394,11 +1197,11 @@ const CODE39_PATTERNS: [[u8; 9]; 44] = [
     [0,1,1,0,1,0,0,0,0], // Z
     [0,1,0,0,0,0,1,0,1], // -
     [1,1,0,0,0,0,1,0,0], // .
-    [0,1,0,1,0,1,0,0,0], // (space)
-    [0,1,0,1,0,0,0,1,0], // $
-    [0,1,0,0,0,1,0,1,0], // /
-    [0,0,0,1,0,1,0,1,0], // +
-    [0,1,0,1,0,1,0,0,0], // % (same as space visually — simplified)
+    [0,1,1,0,0,0,1,0,0], // (space)
+    [0,1,0,1,0,1,0,0,0], // $
+    [0,1,0,1,0,0,0,1,0], // /
+    [0,1,0,0,0,1,0,1,0], // +
+    [0,0,0,1,0,1,0,1,0], // %
     [0,1,0,0,1,0,1,0,0], // * (start/stop)
 ];
 
@@ -406,17 +1209,32 @@ fn code39_index(c: char) -> Option<usize> {
     CODE39_CHARS.iter().position(|&b| b == c as u8)
 }
 
-fn encode_code39(text: &str) -> Option<Barcode> {
+/// Optional Code 39 mod-43 check character: sum of the character values
+/// (their position in `0-9A-Z-. $/+%`) mod 43. None if `text` has a
+/// character outside that set.
+pub fn code39_checksum(text: &str) -> Option<char> {
+    let mut sum = 0;
+    for c in text.chars() {
+        match code39_index(c) {
+            Some(idx) if idx < 43 => sum += idx,
+            _ => return None,
+        }
+    }
+    Some(CODE39_CHARS[sum % 43] as char)
+}
+
+/// `wide` and `gap` are in narrow widths; the gap after each character is
+/// light and carries no data.
+fn encode_code39(text: &str, wide: u8, gap: u8) -> Result<Barcode, EncodeError> {
     let upper = text.to_ascii_uppercase();
 
     // Validate
-    if !upper.chars().all(|c| code39_index(c).is_some()) {
-        return None;
+    if let Some(c) = upper.chars().find(|&c| code39_index(c).is_none()) {
+        return Err(EncodeError::InvalidChar(c));
     }
 
     let narrow = 1u8;
-    let wide = 3u8;
-    let mut modules = Vec::new();
+    let mut modules = ModuleBits::new();
 
     // Quiet zone
     for _ in 0..10 {
@@ -428,13 +1246,17 @@ fn encode_code39(text: &str) -> Option<Barcode> {
     encode_code39_char(&CODE39_PATTERNS[star_idx], narrow, wide, &mut modules);
 
     // Inter-character gap
-    modules.push(false);
+    for _ in 0..narrow * gap {
+        modules.push(false);
+    }
 
     // Data characters
     for c in upper.chars() {
         if let Some(idx) = code39_index(c) {
             encode_code39_char(&CODE39_PATTERNS[idx], narrow, wide, &mut modules);
-            modules.push(false); // inter-character gap
+            for _ in 0..narrow * gap {
+                modules.push(false); // inter-character gap
+            }
         }
     }
 
@@ -446,14 +1268,45 @@ fn encode_code39(text: &str) -> Option<Barcode> {
         modules.push(false);
     }
 
-    Some(Barcode {
+    Ok(Barcode {
         modules,
         text: upper,
         format: BarcodeFormat::Code39,
     })
 }
 
-fn encode_code39_char(pattern: &[u8; 9], narrow: u8, wide: u8, modules: &mut Vec<bool>) {
+/// Decode a Code 39 module stream back to its text, for round-trip checks.
+/// Skips the quiet zones and the `*` start/stop characters. Elements are
+/// classified as wide when they are more than 1.5x the narrowest run.
+pub fn decode_code39(modules: &ModuleBits) -> Option<String> {
+    let runs = modules.runs();
+    let first = runs.iter().position(|r| r.0)?;
+    let last = runs.iter().rposition(|r| r.0)?;
+    let elements = &runs[first..=last];
+
+    let narrow = elements.iter().map(|r| r.1).min()?;
+    let mut chars = Vec::new();
+    let mut i = 0;
+    while i + 9 <= elements.len() {
+        let mut pattern = [0u8; 9];
+        for (j, p) in pattern.iter_mut().enumerate() {
+            *p = (elements[i + j].1 * 2 > narrow * 3) as u8;
+        }
+        let idx = CODE39_PATTERNS.iter().position(|p| *p == pattern)?;
+        chars.push(CODE39_CHARS[idx] as char);
+        // Skip the inter-character gap
+        i += 10;
+    }
+    if i != elements.len() + 1 || chars.len() < 2 {
+        return None;
+    }
+    if chars[0] != '*' || chars[chars.len() - 1] != '*' {
+        return None;
+    }
+    Some(chars[1..chars.len() - 1].iter().collect())
+}
+
+fn encode_code39_char(pattern: &[u8; 9], narrow: u8, wide: u8, modules: &mut ModuleBits) {
     for (i, &is_wide) in pattern.iter().enumerate() {
         let dark = i % 2 == 0; // even = bar, odd = space
         let width = if is_wide != 0 { wide } else { narrow };
@@ -535,41 +1388,106 @@ pub fn ean13_check_digit(digits: &[u8]) -> u8 {
     ((10 - (sum % 10)) % 10) as u8
 }
 
-fn encode_ean13(text: &str) -> Option<Barcode> {
-    if !text.chars().all(|c| c.is_ascii_digit()) {
-        return None;
+/// ISBN-10 check character: the nine digits weighted 10 down to 2, mod 11,
+/// with a check of 10 written as `X`.
+pub fn isbn10_check_char(digits: &[u8]) -> char {
+    let sum: u32 = digits.iter().take(9).enumerate().map(|(i, &d)| (10 - i as u32) * d as u32).sum();
+    match (11 - sum % 11) % 11 {
+        10 => 'X',
+        check => (b'0' + check as u8) as char,
     }
+}
 
-    let mut digits: Vec<u8> = text.chars().map(|c| c as u8 - b'0').collect();
+/// Nine digits and a check character that is a digit or `X`.
+pub fn is_isbn10(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() == 10
+        && bytes[..9].iter().all(|b| b.is_ascii_digit())
+        && (bytes[9].is_ascii_digit() || bytes[9] == b'X' || bytes[9] == b'x')
+}
 
-    // Pad to 12 digits if needed, compute check digit
-    if digits.len() < 12 {
-        return None; // Need at least 12 digits (+ auto check)
+/// Turn an ISBN-10 into the 12 data digits of its 978 EAN-13, after checking
+/// the mod-11 check character. The EAN check digit is left to the encoder.
+pub fn isbn10_to_ean13(text: &str) -> Result<String, EncodeError> {
+    if !is_isbn10(text) {
+        return Err(EncodeError::WrongLength { got: text.len(), min: 10, max: 10 });
     }
-    if digits.len() == 12 {
-        let check = ean13_check_digit(&digits);
-        digits.push(check);
+    let digits: Vec<u8> = text.bytes().take(9).map(|b| b - b'0').collect();
+    let expected = isbn10_check_char(&digits);
+    let got = text.as_bytes()[9].to_ascii_uppercase() as char;
+    if got != expected {
+        return Err(EncodeError::BadIsbnCheck { expected, got });
     }
-    if digits.len() != 13 {
+    Ok(alloc::format!("978{}", &text[..9]))
+}
+
+/// Left-pad a digit string shorter than 12 with zeros to the 12 data digits
+/// of an EAN-13, for the Zero-Pad EAN setting. Anything else, an ISBN-10
+/// included, comes back unchanged.
+pub fn ean13_zero_pad(text: &str) -> String {
+    if text.len() < 12 && text.bytes().all(|b| b.is_ascii_digit()) && !is_isbn10(text) {
+        alloc::format!("{:0>12}", text)
+    } else {
+        String::from(text)
+    }
+}
+
+/// The 12-digit UPC-A that a 13-digit EAN with a leading 0 also is. The bars
+/// are the same; only the printed number drops the 0.
+pub fn ean13_to_upca(digits: &str) -> Option<String> {
+    if digits.len() != 13 || !digits.bytes().all(|b| b.is_ascii_digit()) {
         return None;
     }
+    digits.strip_prefix('0').map(String::from)
+}
 
-    // Verify check digit
-    let expected = ean13_check_digit(&digits[..12]);
-    if digits[12] != expected {
-        // Auto-correct check digit
-        digits[12] = expected;
+/// Settle the last digit of a full-length number according to `mode`.
+fn apply_check_mode(digit: &mut u8, expected: u8, mode: CheckMode) -> Result<(), EncodeError> {
+    match mode {
+        CheckMode::Recompute => *digit = expected,
+        CheckMode::Require if *digit != expected => {
+            return Err(EncodeError::BadCheckDigit { expected, got: *digit });
+        }
+        CheckMode::Require | CheckMode::Append => {}
     }
+    Ok(())
+}
 
-    let mut modules = Vec::new();
+fn encode_ean13(text: &str, mode: CheckMode) -> Result<Barcode, EncodeError> {
+    // An ISBN-10 goes out as its Bookland EAN, with both numbers shown
+    if is_isbn10(text) {
+        let mut barcode = encode_ean13(&isbn10_to_ean13(text)?, mode)?;
+        barcode.text = alloc::format!("{} / ISBN {}", barcode.text, text.to_ascii_uppercase());
+        return Ok(barcode);
+    }
 
-    // Quiet zone
-    for _ in 0..9 {
-        modules.push(false);
+    if let Some(c) = text.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(EncodeError::InvalidChar(c));
     }
 
-    // Start guard: 101
-    modules.push(true);
+    let mut digits: Vec<u8> = text.chars().map(|c| c as u8 - b'0').collect();
+
+    // Need 12 digits (+ auto check) or 13 with check
+    if digits.len() < 12 || digits.len() > 13 {
+        return Err(EncodeError::WrongLength { got: digits.len(), min: 12, max: 13 });
+    }
+    if digits.len() == 12 {
+        let check = ean13_check_digit(&digits);
+        digits.push(check);
+    } else {
+        let expected = ean13_check_digit(&digits[..12]);
+        apply_check_mode(&mut digits[12], expected, mode)?;
+    }
+
+    let mut modules = ModuleBits::new();
+
+    // Quiet zone
+    for _ in 0..9 {
+        modules.push(false);
+    }
+
+    // Start guard: 101
+    modules.push(true);
     modules.push(false);
     modules.push(true);
 
@@ -611,7 +1529,7 @@ fn encode_ean13(text: &str) -> Option<Barcode> {
     // Build display text with check digit
     let display: String = digits.iter().map(|d| (d + b'0') as char).collect();
 
-    Some(Barcode {
+    Ok(Barcode {
         modules,
         text: display,
         format: BarcodeFormat::Ean13,
@@ -620,27 +1538,23 @@ fn encode_ean13(text: &str) -> Option<Barcode> {
 
 // ─── UPC-A ──────────────────────────────────────────────────────────────────
 
-fn encode_upc_a(text: &str) -> Option<Barcode> {
-    if !text.chars().all(|c| c.is_ascii_digit()) {
-        return None;
+fn encode_upc_a(text: &str, mode: CheckMode) -> Result<Barcode, EncodeError> {
+    if let Some(c) = text.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(EncodeError::InvalidChar(c));
     }
 
     let mut digits: Vec<u8> = text.chars().map(|c| c as u8 - b'0').collect();
 
-    if digits.len() < 11 {
-        return None;
+    if digits.len() < 11 || digits.len() > 12 {
+        return Err(EncodeError::WrongLength { got: digits.len(), min: 11, max: 12 });
     }
     if digits.len() == 11 {
         let check = upc_check_digit(&digits);
         digits.push(check);
+    } else {
+        let expected = upc_check_digit(&digits[..11]);
+        apply_check_mode(&mut digits[11], expected, mode)?;
     }
-    if digits.len() != 12 {
-        return None;
-    }
-
-    // Verify/correct check digit
-    let expected = upc_check_digit(&digits[..11]);
-    digits[11] = expected;
 
     // UPC-A is EAN-13 with a leading 0
     let mut ean_digits = vec![0u8];
@@ -648,18 +1562,17 @@ fn encode_upc_a(text: &str) -> Option<Barcode> {
 
     let display: String = digits.iter().map(|d| (d + b'0') as char).collect();
 
-    // Encode as EAN-13 with leading 0
+    // Encode as EAN-13 with leading 0. The check digit is already settled,
+    // and is the same digit either way.
     let ean_text: String = ean_digits.iter().map(|d| (d + b'0') as char).collect();
-    if let Some(mut barcode) = encode_ean13(&ean_text) {
-        barcode.text = display;
-        barcode.format = BarcodeFormat::UpcA;
-        Some(barcode)
-    } else {
-        None
-    }
+    let mut barcode = encode_ean13(&ean_text, CheckMode::Append)?;
+    barcode.text = display;
+    barcode.format = BarcodeFormat::UpcA;
+    Ok(barcode)
 }
 
-fn upc_check_digit(digits: &[u8]) -> u8 {
+/// Compute UPC-A check digit.
+pub fn upc_check_digit(digits: &[u8]) -> u8 {
     let mut sum = 0u32;
     for (i, &d) in digits.iter().enumerate() {
         if i % 2 == 0 {
@@ -670,3 +1583,939 @@ fn upc_check_digit(digits: &[u8]) -> u8 {
     }
     ((10 - (sum % 10)) % 10) as u8
 }
+
+/// GS1 price check digit for a four-digit price field: weights 2-, 2, 3 and
+/// 5- applied digit by digit (see `PRICE_WEIGHT_*`), summed, times 3, units.
+pub fn price_check_digit(price: &[u8; 4]) -> u8 {
+    let sum = PRICE_WEIGHT_2_MINUS[price[0] as usize]
+        + (price[1] * 2) % 10
+        + (price[2] * 3) % 10
+        + PRICE_WEIGHT_5_MINUS[price[3] as usize];
+    (sum * 3) % 10
+}
+
+/// Weight "2-": units digit of 2d less its tens digit, mod 10.
+const PRICE_WEIGHT_2_MINUS: [u8; 10] = [0, 2, 4, 6, 8, 9, 1, 3, 5, 7];
+/// Weight "5-": units digit of 5d less its tens digit, mod 10.
+const PRICE_WEIGHT_5_MINUS: [u8; 10] = [0, 5, 9, 4, 8, 3, 7, 2, 6, 1];
+
+/// UPC-A for a variable-weight item, number system 2: `2`, the five-digit
+/// item number, the price check digit, the price in cents as four digits,
+/// then the UPC check digit. `price` may be shorter than four digits.
+pub fn variable_weight_upca(item: &str, price: &str) -> Result<String, EncodeError> {
+    if let Some(c) = item.chars().chain(price.chars()).find(|c| !c.is_ascii_digit()) {
+        return Err(EncodeError::InvalidChar(c));
+    }
+    if item.len() != 5 {
+        return Err(EncodeError::WrongLength { got: item.len(), min: 5, max: 5 });
+    }
+    if price.is_empty() || price.len() > 4 {
+        return Err(EncodeError::WrongLength { got: price.len(), min: 1, max: 4 });
+    }
+    let price = alloc::format!("{:0>4}", price);
+    let mut price_digits = [0u8; 4];
+    for (d, b) in price_digits.iter_mut().zip(price.bytes()) {
+        *d = b - b'0';
+    }
+    let data = alloc::format!("2{}{}{}", item, price_check_digit(&price_digits), price);
+    let digits: Vec<u8> = data.bytes().map(|b| b - b'0').collect();
+    Ok(alloc::format!("{}{}", data, upc_check_digit(&digits)))
+}
+
+// ─── Telepen ────────────────────────────────────────────────────────────────
+
+const TELEPEN_START: u8 = b'_';
+const TELEPEN_STOP: u8 = b'z';
+
+/// Telepen check character: 127 minus the sum of the data values mod 127.
+pub fn telepen_checksum(values: &[u8]) -> u8 {
+    let sum: u32 = values.iter().map(|&v| v as u32).sum();
+    ((127 - sum % 127) % 127) as u8
+}
+
+/// Append one Telepen character. The 7 data bits plus an even parity bit are
+/// read LSB first and turned into bar/space width pairs (1 = narrow, 3 = wide):
+/// `1` -> 1,1; `00` -> 3,1; `010` -> 3,3; `01..10` -> 1,3 then 1,1 per inner
+/// `1` then 1,3. Every character comes out 16 modules wide.
+fn encode_telepen_char(value: u8, modules: &mut ModuleBits) {
+    let parity = (value.count_ones() & 1) as u8;
+    let byte = (value & 0x7F) | (parity << 7);
+    let bits: Vec<bool> = (0..8).map(|i| byte & (1 << i) != 0).collect();
+
+    let mut widths: Vec<u8> = Vec::new();
+    let mut i = 0;
+    while i < bits.len() {
+        if bits[i] {
+            widths.extend_from_slice(&[1, 1]);
+            i += 1;
+        } else if i + 1 < bits.len() && !bits[i + 1] {
+            widths.extend_from_slice(&[3, 1]);
+            i += 2;
+        } else {
+            // A 0, a run of 1s, and the 0 that closes it
+            let close = (i + 1..bits.len()).find(|&j| !bits[j]).unwrap_or(bits.len() - 1);
+            let ones = close - i - 1;
+            if ones == 1 {
+                widths.extend_from_slice(&[3, 3]);
+            } else {
+                widths.extend_from_slice(&[1, 3]);
+                for _ in 0..ones - 2 {
+                    widths.extend_from_slice(&[1, 1]);
+                }
+                widths.extend_from_slice(&[1, 3]);
+            }
+            i = close + 1;
+        }
+    }
+
+    for (k, &w) in widths.iter().enumerate() {
+        let dark = k % 2 == 0;
+        for _ in 0..w {
+            modules.push(dark);
+        }
+    }
+}
+
+fn telepen_symbol(values: &[u8], text: String) -> Barcode {
+    let mut modules = ModuleBits::new();
+
+    // Quiet zone
+    for _ in 0..10 {
+        modules.push(false);
+    }
+
+    encode_telepen_char(TELEPEN_START, &mut modules);
+    for &v in values {
+        encode_telepen_char(v, &mut modules);
+    }
+    encode_telepen_char(telepen_checksum(values), &mut modules);
+    encode_telepen_char(TELEPEN_STOP, &mut modules);
+
+    // Quiet zone
+    for _ in 0..10 {
+        modules.push(false);
+    }
+
+    Barcode {
+        modules,
+        text,
+        format: BarcodeFormat::Telepen,
+    }
+}
+
+/// Telepen in full ASCII mode: one character per symbol character.
+fn encode_telepen(text: &str) -> Result<Barcode, EncodeError> {
+    if let Some(c) = text.chars().find(|&c| !c.is_ascii()) {
+        return Err(EncodeError::InvalidChar(c));
+    }
+    Ok(telepen_symbol(text.as_bytes(), String::from(text)))
+}
+
+/// Telepen in double-density numeric mode: each digit pair is one symbol
+/// character (value + 27), and an odd-length input gets a leading zero.
+/// Readers must be set to Telepen Numeric to read it back as digits.
+pub fn encode_telepen_numeric(text: &str) -> Result<Barcode, EncodeError> {
+    if text.is_empty() {
+        return Err(EncodeError::Empty);
+    }
+    if let Some(c) = text.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(EncodeError::InvalidChar(c));
+    }
+    let mut digits: Vec<u8> = text.bytes().map(|b| b - b'0').collect();
+    if digits.len() % 2 == 1 {
+        digits.insert(0, 0);
+    }
+    let values: Vec<u8> = digits.chunks(2).map(|p| p[0] * 10 + p[1] + 27).collect();
+    let display: String = digits.iter().map(|d| (d + b'0') as char).collect();
+    Ok(telepen_symbol(&values, display))
+}
+
+// ─── Standard 2 of 5 ────────────────────────────────────────────────────────
+
+/// Standard (Industrial) 2 of 5 bar widths per digit: five bars, two of them
+/// wide. Spaces are always narrow.
+const STD2OF5_BARS: [[u8; 5]; 10] = [
+    [1,1,3,3,1], // 0
+    [3,1,1,1,3], // 1
+    [1,3,1,1,3], // 2
+    [3,3,1,1,1], // 3
+    [1,1,3,1,3], // 4
+    [3,1,3,1,1], // 5
+    [1,3,3,1,1], // 6
+    [1,1,1,3,3], // 7
+    [3,1,1,3,1], // 8
+    [1,3,1,3,1], // 9
+];
+
+/// Start and stop as alternating bar/space widths. As above, 3 stands for
+/// whatever the wide width is.
+const STD2OF5_START: [u8; 6] = [3,1,3,1,1,1];
+const STD2OF5_STOP: [u8; 5] = [3,1,1,1,3];
+
+/// Optional Standard 2 of 5 mod-10 check digit: weight 3 on the rightmost
+/// digit, alternating with 1 leftwards. Returns `None` on a non-digit.
+pub fn std2of5_check_digit(text: &str) -> Option<char> {
+    let mut sum = 0u32;
+    for (i, c) in text.chars().rev().enumerate() {
+        let d = c.to_digit(10)?;
+        sum += if i % 2 == 0 { d * 3 } else { d };
+    }
+    Some((b'0' + ((10 - sum % 10) % 10) as u8) as char)
+}
+
+/// Push alternating bar/space widths, bar first. 3 stands for the wide
+/// width and 4 for one module more than that.
+fn push_widths(widths: &[u8], wide: u8, modules: &mut ModuleBits) {
+    for (i, &w) in widths.iter().enumerate() {
+        let dark = i % 2 == 0;
+        let w = if w > 1 { wide + w - 3 } else { w };
+        for _ in 0..w {
+            modules.push(dark);
+        }
+    }
+}
+
+fn encode_std2of5(text: &str, wide: u8) -> Result<Barcode, EncodeError> {
+    if let Some(c) = text.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(EncodeError::InvalidChar(c));
+    }
+
+    let mut modules = ModuleBits::new();
+
+    // Quiet zone
+    for _ in 0..10 {
+        modules.push(false);
+    }
+
+    push_widths(&STD2OF5_START, wide, &mut modules);
+    for b in text.bytes() {
+        for &w in STD2OF5_BARS[(b - b'0') as usize].iter() {
+            push_widths(&[w, 1], wide, &mut modules);
+        }
+    }
+    push_widths(&STD2OF5_STOP, wide, &mut modules);
+
+    // Quiet zone
+    for _ in 0..10 {
+        modules.push(false);
+    }
+
+    Ok(Barcode {
+        modules,
+        text: String::from(text),
+        format: BarcodeFormat::Std2of5,
+    })
+}
+
+// ─── Matrix 2 of 5 ──────────────────────────────────────────────────────────
+
+// Each digit is three bars and the two spaces between them, using the
+// `STD2OF5_BARS` wide/narrow pattern, then a narrow space. Start and stop
+// open with an extra-wide bar.
+const MATRIX2OF5_START: [u8; 6] = [4,1,1,1,1,1];
+const MATRIX2OF5_STOP: [u8; 5] = [4,1,1,1,1];
+
+fn encode_matrix2of5(text: &str, wide: u8) -> Result<Barcode, EncodeError> {
+    if let Some(c) = text.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(EncodeError::InvalidChar(c));
+    }
+
+    let mut modules = ModuleBits::new();
+
+    // Quiet zone
+    for _ in 0..10 {
+        modules.push(false);
+    }
+
+    push_widths(&MATRIX2OF5_START, wide, &mut modules);
+    for b in text.bytes() {
+        push_widths(&STD2OF5_BARS[(b - b'0') as usize], wide, &mut modules);
+        modules.push(false);
+    }
+    push_widths(&MATRIX2OF5_STOP, wide, &mut modules);
+
+    // Quiet zone
+    for _ in 0..10 {
+        modules.push(false);
+    }
+
+    Ok(Barcode {
+        modules,
+        text: String::from(text),
+        format: BarcodeFormat::Matrix2of5,
+    })
+}
+
+// ─── Code 32 ────────────────────────────────────────────────────────────────
+
+/// Code 32 base-32 digits: 0-9 and the consonants, no vowels.
+const CODE32_ALPHABET: &[u8; 32] = b"0123456789BCDFGHJKLMNPQRSTUVWXYZ";
+
+/// Code 32 (AIC) check digit over the first 8 digits: digits in odd positions
+/// are added, digits in even positions are doubled and their digit sums added.
+pub fn code32_check_digit(digits: &[u8]) -> u8 {
+    let mut sum = 0u32;
+    for (i, &d) in digits.iter().take(8).enumerate() {
+        if i % 2 == 1 {
+            let doubled = d as u32 * 2;
+            sum += doubled / 10 + doubled % 10;
+        } else {
+            sum += d as u32;
+        }
+    }
+    (sum % 10) as u8
+}
+
+/// Italian pharmacode: the 9-digit AIC number written in base 32 as six
+/// Code 39 characters. Takes 8 digits, or 9 with the check digit settled
+/// by `mode`.
+fn encode_code32(text: &str, mode: CheckMode, wide: u8, gap: u8) -> Result<Barcode, EncodeError> {
+    if let Some(c) = text.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(EncodeError::InvalidChar(c));
+    }
+
+    let mut digits: Vec<u8> = text.bytes().map(|b| b - b'0').collect();
+    if digits.len() < 8 || digits.len() > 9 {
+        return Err(EncodeError::WrongLength { got: digits.len(), min: 8, max: 9 });
+    }
+    let expected = code32_check_digit(&digits);
+    match digits.get_mut(8) {
+        Some(digit) => apply_check_mode(digit, expected, mode)?,
+        None => digits.push(expected),
+    }
+
+    let mut value = digits.iter().fold(0u32, |acc, &d| acc * 10 + d as u32);
+    let mut base32 = [b'0'; 6];
+    for slot in base32.iter_mut().rev() {
+        *slot = CODE32_ALPHABET[(value % 32) as usize];
+        value /= 32;
+    }
+
+    let symbol: String = base32.iter().map(|&b| b as char).collect();
+    let mut barcode = encode_code39(&symbol, wide, gap)?;
+    let mut display = String::from("A");
+    display.extend(digits.iter().map(|d| (d + b'0') as char));
+    barcode.text = display;
+    barcode.format = BarcodeFormat::Code32;
+    Ok(barcode)
+}
+
+/// Turn the six base-32 characters of a decoded Code 32 back into "A" and
+/// the 9-digit AIC number.
+fn code32_from_base32(symbol: &str) -> Option<String> {
+    if symbol.len() != 6 {
+        return None;
+    }
+    let mut value = 0u32;
+    for b in symbol.bytes() {
+        let digit = CODE32_ALPHABET.iter().position(|&a| a == b)?;
+        value = value * 32 + digit as u32;
+    }
+    if value >= 1_000_000_000 {
+        return None;
+    }
+    Some(alloc::format!("A{:09}", value))
+}
+
+// ─── PZN ────────────────────────────────────────────────────────────────────
+
+/// PZN-8 check digit: the 7 payload digits weighted 1..7, mod 11. `None`
+/// when that comes to 10, as such numbers are never issued.
+pub fn pzn_check_digit(digits: &[u8]) -> Option<u8> {
+    let sum: u32 = digits.iter().take(7).enumerate().map(|(i, &d)| (i as u32 + 1) * d as u32).sum();
+    match (sum % 11) as u8 {
+        10 => None,
+        check => Some(check),
+    }
+}
+
+/// German Pharmazentralnummer: `-`, 7 digits and the check digit, as Code 39.
+fn encode_pzn(text: &str, wide: u8, gap: u8) -> Result<Barcode, EncodeError> {
+    if let Some(c) = text.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(EncodeError::InvalidChar(c));
+    }
+
+    let mut digits: Vec<u8> = text.bytes().map(|b| b - b'0').collect();
+    if digits.len() != 7 {
+        return Err(EncodeError::WrongLength { got: digits.len(), min: 7, max: 7 });
+    }
+    digits.push(pzn_check_digit(&digits).ok_or(EncodeError::NoCheckDigit)?);
+
+    let number: String = digits.iter().map(|d| (d + b'0') as char).collect();
+    let mut barcode = encode_code39(&alloc::format!("-{}", number), wide, gap)?;
+    barcode.text = alloc::format!("PZN - {}", number);
+    barcode.format = BarcodeFormat::Pzn;
+    Ok(barcode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upc_a_matches_its_ean13_form() {
+        for upc in ["036000291452", "012345678905", "042100005264", "725272730706"] {
+            let ean = alloc::format!("0{}", upc);
+            let a = encode(upc, BarcodeFormat::UpcA).unwrap();
+            let b = encode(&ean, BarcodeFormat::Ean13).unwrap();
+            assert_eq!(a.modules, b.modules, "{}", upc);
+            assert_eq!(a.text, upc);
+            assert_eq!(a.format, BarcodeFormat::UpcA);
+        }
+        // An 11-digit UPC-A gets the same check digit as its EAN-13 form
+        let a = encode("04210000526", BarcodeFormat::UpcA).unwrap();
+        let b = encode("004210000526", BarcodeFormat::Ean13).unwrap();
+        assert_eq!(a.modules, b.modules);
+    }
+
+    /// Modules as a `1`/`0` string, quiet zones left off.
+    fn symbol_bits(barcode: &Barcode) -> String {
+        let bits = barcode.modules.to_vec();
+        let first = bits.iter().position(|&d| d).unwrap();
+        let last = bits.iter().rposition(|&d| d).unwrap();
+        bits[first..=last].iter().map(|&d| if d { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn module_bits_match_a_bool_vector() {
+        for &format in BarcodeFormat::all() {
+            let text = match format {
+                BarcodeFormat::Ean13 => "590123412345",
+                BarcodeFormat::UpcA => "03600029145",
+                BarcodeFormat::Code32 => "01234567",
+                BarcodeFormat::Pzn => "0123456",
+                BarcodeFormat::Code39 => "PRECURSOR-123",
+                BarcodeFormat::Std2of5 | BarcodeFormat::Matrix2of5 => "1234567",
+                _ => "Hello, World!",
+            };
+            let barcode = encode(text, format).unwrap();
+            let bools = barcode.modules.to_vec();
+            assert_eq!(bools.len(), barcode.modules.len(), "{:?}", format);
+            for (i, &dark) in bools.iter().enumerate() {
+                assert_eq!(barcode.modules.get(i), dark, "{:?} module {}", format, i);
+            }
+            assert!(!barcode.modules.get(bools.len()), "{:?} past the end", format);
+            // Packing the bools again gives the same stream
+            let repacked: ModuleBits = bools.iter().copied().collect();
+            assert_eq!(repacked, barcode.modules, "{:?}", format);
+            let widths: usize = barcode.modules.runs().iter().map(|&(_, w)| w).sum();
+            assert_eq!(widths, bools.len(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn module_bits_across_byte_boundaries() {
+        let bools: Vec<bool> = (0..29).map(|i| i % 3 == 0 || i == 8).collect();
+        let mut bits = ModuleBits::new();
+        bits.extend_from_slice(&bools);
+        assert_eq!(bits.len(), 29);
+        assert_eq!(bits.to_vec(), bools);
+        assert_eq!(bits.iter().len(), 29);
+    }
+
+    /// Symbol values of a Code 128 symbol: start code through check value.
+    fn code128_values(barcode: &Barcode) -> Vec<usize> {
+        let bits = symbol_bits(barcode);
+        let body = &bits.as_bytes()[..bits.len() - 13];
+        body.chunks(11)
+            .map(|chunk| {
+                let mut widths = [0u8; 6];
+                let mut elem = 0;
+                for (j, &b) in chunk.iter().enumerate() {
+                    if j > 0 && b != chunk[j - 1] {
+                        elem += 1;
+                    }
+                    widths[elem] += 1;
+                }
+                CODE128_PATTERNS.iter().position(|p| *p == widths).unwrap()
+            })
+            .collect()
+    }
+
+    /// Symbol values between the start code and the check value.
+    fn code128_data(text: &str) -> Vec<usize> {
+        let values = code128_values(&encode(text, BarcodeFormat::Code128).unwrap());
+        values[1..values.len() - 1].to_vec()
+    }
+
+    #[test]
+    fn code128_fnc1() {
+        assert_eq!(code128_data("AB{FNC1}C"), [33, 34, FNC1, 35]);
+        // GS1-128: FNC1 first, then the digits in subset C
+        let barcode = encode("{FNC1}0101234567890128", BarcodeFormat::Code128).unwrap();
+        assert_eq!(code128_values(&barcode)[..3], [START_C, FNC1, 1]);
+        assert_eq!(barcode.text, "0101234567890128");
+        assert_eq!(barcode.verify(), Some(true));
+    }
+
+    #[test]
+    fn code128_fnc2() {
+        let barcode = encode("AB{FNC2}C", BarcodeFormat::Code128).unwrap();
+        assert_eq!(code128_data("AB{FNC2}C"), [33, 34, 97, 35]);
+        assert_eq!(barcode.text, "ABC");
+        assert_eq!(barcode.verify(), Some(true));
+    }
+
+    #[test]
+    fn code128_fnc3() {
+        assert_eq!(code128_data("AB{FNC3}C"), [33, 34, 96, 35]);
+        // Not in subset C: switch to B first
+        assert_eq!(code128_data("1234{FNC3}56"), [12, 34, CODE_B, 96, 21, 22]);
+        assert_eq!(encode("1234{FNC3}56", BarcodeFormat::Code128).unwrap().verify(), Some(true));
+    }
+
+    #[test]
+    fn code128_fnc4() {
+        // FNC4 in B, then 'A' less 32: reads as 'A' + 128
+        let barcode = encode("{FNC4}A", BarcodeFormat::Code128).unwrap();
+        assert_eq!(code128_data("{FNC4}A"), [CODE_B, 33]);
+        assert_eq!(barcode.text, "\u{C1}");
+        assert_eq!(barcode.verify(), Some(true));
+        // FNC4 in A is CODE_A's value
+        assert_eq!(code128_data("\x01{FNC4}A"), [65, CODE_A, 33]);
+        // Nothing to shift
+        assert!(encode("AB{FNC4}", BarcodeFormat::Code128).is_err());
+        assert!(encode("{FNC4}{FNC1}", BarcodeFormat::Code128).is_err());
+    }
+
+    #[test]
+    fn code128_round_trip() {
+        let texts = [
+            "Hello, World!",
+            "12345678",
+            "1234567",
+            "ABC123456789def",
+            "\x01\x02abc1234",
+            "a\tb\rc",
+            "X\x1b99887766Y",
+            "LOT-2024-000123-A/77",
+            "0",
+        ];
+        for text in texts {
+            let barcode = encode(text, BarcodeFormat::Code128).unwrap();
+            assert_eq!(decode_code128(&barcode.modules).as_deref(), Some(text), "{:?}", text);
+            assert_eq!(barcode.verify(), Some(true), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn code128_decode_rejects_a_bad_checksum() {
+        let barcode = encode("Hello", BarcodeFormat::Code128).unwrap();
+        let mut bits = barcode.modules.to_vec();
+        // Swap the first symbol after the start code for another value
+        let first = bits.iter().position(|&d| d).unwrap();
+        let other = pattern_to_modules(&CODE128_PATTERNS[0]);
+        bits[first + 11..first + 22].copy_from_slice(&other);
+        let modules: ModuleBits = bits.into_iter().collect();
+        assert_eq!(decode_code128(&modules), None);
+    }
+
+    #[test]
+    fn code128_latin1_round_trip() {
+        let options = EncodeOptions { code128_latin1: true, ..EncodeOptions::default() };
+        for text in ["Ä1é", "café", "Straße 12345678", "\u{80}\u{81}x", "ÿ", "\u{A0}é\u{9F}", "12345678ü"] {
+            let barcode = encode_with(text, BarcodeFormat::Code128, options).unwrap();
+            assert_eq!(barcode.text, text);
+            assert_eq!(decode_code128(&barcode.modules).as_deref(), Some(text), "{:?}", text);
+        }
+        // 'Ä' (0xC4) is FNC4 and 'D' (0x44) in B
+        let barcode = encode_with("Ä1é", BarcodeFormat::Code128, options).unwrap();
+        assert_eq!(code128_values(&barcode)[..3], [START_B, CODE_B, 36]);
+        // Off by default
+        assert_eq!(encode("Ä1é", BarcodeFormat::Code128).unwrap_err(), EncodeError::InvalidChar('Ä'));
+    }
+
+    #[test]
+    fn code128_decodes_an_fnc4_latch() {
+        // FNC4 FNC4 latches Latin-1 on; a single FNC4 then shifts back out
+        let mut values = vec![START_B, CODE_B, CODE_B, 33, 34, CODE_B, 35, 36];
+        values.push(code128_checksum(&values));
+        let mut modules = ModuleBits::new();
+        for &v in &values {
+            modules.extend(pattern_to_modules(&CODE128_PATTERNS[v]));
+        }
+        modules.extend_from_slice(&CODE128_STOP);
+        assert_eq!(decode_code128(&modules).as_deref(), Some("ÁÂCÄ"));
+    }
+
+    #[test]
+    fn code128_validation_matches_the_encoder() {
+        assert_eq!(first_invalid_char("ab~", BarcodeFormat::Code128), None);
+        assert!(encode("ab~", BarcodeFormat::Code128).is_ok());
+        assert_eq!(first_invalid_char("ab\x7f", BarcodeFormat::Code128), Some(2));
+        assert!(matches!(encode("ab\x7f", BarcodeFormat::Code128), Err(EncodeError::InvalidChar('\x7f'))));
+    }
+
+    /// Data symbols between the start code and the check value, after
+    /// checking the symbol decodes back to `text`.
+    fn code128_symbol_count(text: &str) -> usize {
+        let barcode = encode(text, BarcodeFormat::Code128).unwrap();
+        assert_eq!(decode_code128(&barcode.modules).as_deref(), Some(text));
+        code128_data(text).len()
+    }
+
+    #[test]
+    fn code128_odd_leading_digit_run() {
+        // Start C for the pairs, then one switch to B for the odd digit
+        assert_eq!(code128_data("12345"), [12, 34, CODE_B, 21]);
+        assert_eq!(code128_symbol_count("12345"), 4);
+        assert_eq!(code128_data("123456A"), [12, 34, 56, CODE_B, 33]);
+        assert_eq!(code128_symbol_count("123456A"), 5);
+        assert_eq!(code128_data("1234A"), [12, 34, CODE_B, 33]);
+        assert_eq!(code128_symbol_count("1234A"), 4);
+    }
+
+    #[test]
+    fn code128_interior_digit_runs() {
+        // Eight digits after letters: C saves four symbols for the one switch
+        assert_eq!(code128_data("AB12345678"), [33, 34, CODE_C, 12, 34, 56, 78]);
+        assert_eq!(code128_symbol_count("AB12345678"), 7);
+        assert_eq!(encode_code128("AB12345678", true, false).unwrap().modules.len(), 11 * 12 + 13 + 20);
+        // An odd interior run keeps its first digit in B and switches back after
+        assert_eq!(code128_data("X1234567Y"), [56, 17, CODE_C, 23, 45, 67, CODE_B, 57]);
+        assert_eq!(code128_symbol_count("X1234567Y"), 8);
+        // Too short to pay for switching there and back
+        assert_eq!(code128_symbol_count("AB1234CD"), 8);
+        assert!(!code128_data("AB1234CD").contains(&CODE_C));
+    }
+
+    #[test]
+    fn telepen_reference_symbol() {
+        // Start `_` is five narrow bar/space pairs then a wide bar and wide
+        // space, stop `z` the same read backwards, as the Telepen spec has it.
+        // "ABC" sums to 198, 198 mod 127 = 71, and 127 - 71 = 56 is `8`.
+        assert_eq!(telepen_checksum(b"ABC"), b'8');
+        let expected = [
+            "1010101010111000", // _
+            "1011101110111000", // A
+            "1110001110111000", // B
+            "1010111011101010", // C
+            "1110100010100010", // 8
+            "1110001010101010", // z
+        ]
+        .concat();
+        let barcode = encode("ABC", BarcodeFormat::Telepen).unwrap();
+        assert_eq!(symbol_bits(&barcode), expected.trim_end_matches('0'));
+        assert_eq!(barcode.modules.len(), 16 * 6 + 20);
+    }
+
+    #[test]
+    fn code39_round_trip() {
+        let all = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+        for text in ["A", "CODE39", "HELLO WORLD", "12345", "$/+%-.", all] {
+            for wide_ratio in [2, 3] {
+                for code39_gap in [1, 2, 3] {
+                    let options = EncodeOptions { wide_ratio, code39_gap, ..EncodeOptions::default() };
+                    let barcode = encode_with(text, BarcodeFormat::Code39, options).unwrap();
+                    let decoded = decode_code39(&barcode.modules);
+                    assert_eq!(decoded.as_deref(), Some(text), "{} {}:{}", text, wide_ratio, code39_gap);
+                }
+            }
+        }
+        assert_eq!(decode_code39(&encode("lower", BarcodeFormat::Code39).unwrap().modules).as_deref(), Some("LOWER"));
+    }
+
+    #[test]
+    fn code39_decode_rejects_other_symbols() {
+        assert_eq!(decode_code39(&ModuleBits::new()), None);
+        assert_eq!(decode_code39(&encode("CODE39", BarcodeFormat::Code128).unwrap().modules), None);
+        // Cut off before the stop character
+        let bits = encode("AB", BarcodeFormat::Code39).unwrap().modules.to_vec();
+        let cut: ModuleBits = bits[..bits.len() - 20].iter().copied().collect();
+        assert_eq!(decode_code39(&cut), None);
+    }
+
+    #[test]
+    fn code39_ascii_snapshot() {
+        // *A* with the quiet zones, wide elements three narrow ones across
+        let row = "          #   # ### ### # ### # #   # ### #   # ### ### #          ";
+        let barcode = encode("A", BarcodeFormat::Code39).unwrap();
+        assert_eq!(barcode.to_ascii(1), [row, "\n", row, "\n", "A\n"].concat());
+        let wide: String = row.chars().flat_map(|c| [c, c]).collect();
+        assert_eq!(barcode.to_ascii(2), [&wide, "\n", &wide, "\n", "A\n"].concat());
+    }
+
+    #[test]
+    fn aim_identifiers() {
+        let aim = |text: &str, format| encode(text, format).unwrap().aim_identifier();
+        assert_eq!(aim("Hello", BarcodeFormat::Code128), "]C0");
+        assert_eq!(aim("{FNC1}0101234567890128", BarcodeFormat::Code128), "]C1");
+        assert_eq!(aim("{FNC1}10ABC", BarcodeFormat::Code128), "]C1");
+        // FNC1 anywhere else is a separator, not the GS1 flag
+        assert_eq!(aim("AB{FNC1}C", BarcodeFormat::Code128), "]C0");
+        assert_eq!(aim("CODE39", BarcodeFormat::Code39), "]A0");
+        assert_eq!(aim("12345678", BarcodeFormat::Code32), "]A0");
+        assert_eq!(aim("0123456", BarcodeFormat::Pzn), "]A0");
+        assert_eq!(aim("590123412345", BarcodeFormat::Ean13), "]E0");
+        assert_eq!(aim("03600029145", BarcodeFormat::UpcA), "]E0");
+        assert_eq!(aim("ABC", BarcodeFormat::Telepen), "]B0");
+        assert_eq!(aim("12345", BarcodeFormat::Std2of5), "]S0");
+        assert_eq!(aim("12345", BarcodeFormat::Matrix2of5), "]X0");
+    }
+
+    #[test]
+    fn std2of5_reference_symbol() {
+        // Bars only, wide three modules; every space is narrow
+        let expected = [
+            "1110111010",     // start: wide, wide, narrow
+            "11101010101110", // 1
+            "10111010101110", // 2
+            "11101110101010", // 3
+            "10101110101110", // 4
+            "11101011101010", // 5
+            "10111011101010", // 6
+            "10101011101110", // 7
+            "111010111",      // stop: wide, narrow, wide
+        ]
+        .concat();
+        let barcode = encode("1234567", BarcodeFormat::Std2of5).unwrap();
+        assert_eq!(symbol_bits(&barcode), expected);
+        assert_eq!(barcode.text, "1234567");
+        assert_eq!(std2of5_check_digit("1234567"), Some('0'));
+        assert!(encode("12A4", BarcodeFormat::Std2of5).is_err());
+    }
+
+    #[test]
+    fn code32_reference_symbol() {
+        // 01234567 has check digit 6, and 12345676 is 0CSSBD in base 32
+        assert_eq!(code32_check_digit(&[0, 1, 2, 3, 4, 5, 6, 7]), 6);
+        let barcode = encode("01234567", BarcodeFormat::Code32).unwrap();
+        assert_eq!(barcode.text, "A012345676");
+        assert_eq!(decode_code39(&barcode.modules).as_deref(), Some("0CSSBD"));
+        assert_eq!(barcode.verify(), Some(true));
+        assert_eq!(barcode.modules, encode("0CSSBD", BarcodeFormat::Code39).unwrap().modules);
+    }
+
+    #[test]
+    fn code32_check_modes() {
+        let with = |text: &str, check_mode| {
+            encode_with(text, BarcodeFormat::Code32, EncodeOptions { check_mode, ..EncodeOptions::default() })
+        };
+        // Right check digit: the same symbol in every mode
+        for mode in [CheckMode::Recompute, CheckMode::Require, CheckMode::Append] {
+            assert_eq!(with("012345676", mode).unwrap().text, "A012345676");
+        }
+        // Wrong one: corrected, rejected, or kept
+        assert_eq!(with("012345671", CheckMode::Recompute).unwrap().text, "A012345676");
+        let bad = EncodeError::BadCheckDigit { expected: 6, got: 1 };
+        assert_eq!(with("012345671", CheckMode::Require).unwrap_err(), bad);
+        assert_eq!(with("012345671", CheckMode::Append).unwrap().text, "A012345671");
+        assert_eq!(verify_check_digit("012345671", BarcodeFormat::Code32), Err(bad));
+        assert_eq!(verify_check_digit("012345676", BarcodeFormat::Code32), Ok(true));
+    }
+
+    #[test]
+    fn pzn_check_digit_and_symbol() {
+        // 0*1 + 1*2 + 2*3 + 3*4 + 4*5 + 5*6 + 6*7 = 112, and 112 mod 11 = 2
+        let barcode = encode("0123456", BarcodeFormat::Pzn).unwrap();
+        assert_eq!(barcode.text, "PZN - 01234562");
+        assert_eq!(decode_code39(&barcode.modules).as_deref(), Some("-01234562"));
+        assert_eq!(barcode.verify(), Some(true));
+        // 3*7 = 21, and 21 mod 11 = 10: never issued
+        assert_eq!(pzn_check_digit(&[0, 0, 0, 0, 0, 0, 3]), None);
+        assert!(matches!(encode("0000003", BarcodeFormat::Pzn), Err(EncodeError::NoCheckDigit)));
+        assert!(!is_valid("0000003", BarcodeFormat::Pzn));
+        assert!(is_valid("0123456", BarcodeFormat::Pzn));
+        assert!(is_valid("012", BarcodeFormat::Pzn));
+        assert!(!is_valid("01234567", BarcodeFormat::Pzn));
+    }
+
+    #[test]
+    fn wide_ratio_sets_element_widths() {
+        let with = |text: &str, format, wide_ratio| {
+            encode_with(text, format, EncodeOptions { wide_ratio, ..EncodeOptions::default() }).unwrap()
+        };
+        for wide_ratio in [2, 3] {
+            // Each Code 39 character is six narrow and three wide elements
+            let barcode = with("A", BarcodeFormat::Code39, wide_ratio);
+            let widths: Vec<usize> = barcode.modules.runs()[1..10].iter().map(|r| r.1).collect();
+            assert_eq!(widths.iter().filter(|&&w| w == 1).count(), 6);
+            assert_eq!(widths.iter().filter(|&&w| w == wide_ratio as usize).count(), 3);
+            assert_eq!(symbol_bits(&barcode).len(), 3 * (6 + 3 * wide_ratio as usize) + 2);
+            assert_eq!(decode_code39(&barcode.modules).as_deref(), Some("A"));
+            // Standard 2 of 5: two wide bars each in the start, the digit
+            // and the stop, with everything else narrow
+            let barcode = with("7", BarcodeFormat::Std2of5, wide_ratio);
+            assert_eq!(symbol_bits(&barcode).len(), 6 * wide_ratio as usize + 15);
+        }
+        // Out of range ratios are clamped
+        assert_eq!(with("A", BarcodeFormat::Code39, 9).modules, with("A", BarcodeFormat::Code39, 3).modules);
+        assert_eq!(with("A", BarcodeFormat::Code39, 0).modules, with("A", BarcodeFormat::Code39, 2).modules);
+    }
+
+    #[test]
+    fn ean13_zero_padding() {
+        assert_eq!(ean13_zero_pad("03600029145"), "003600029145");
+        assert_eq!(ean13_zero_pad("42"), "000000000042");
+        let barcode = encode(&ean13_zero_pad("03600029145"), BarcodeFormat::Ean13).unwrap();
+        assert_eq!(barcode.text, "0036000291452");
+        assert_eq!(verify_check_digit(&barcode.text, BarcodeFormat::Ean13), Ok(true));
+        // Full-length numbers, ISBN-10s and non-digits are left alone
+        for text in ["590123412345", "5901234123457", "0306406152", "12-34"] {
+            assert_eq!(ean13_zero_pad(text), text);
+        }
+        // Unpadded, a short number is still an error
+        assert!(encode("03600029145", BarcodeFormat::Ean13).is_err());
+    }
+
+    #[test]
+    fn ean_upc_check_modes() {
+        let with = |text: &str, format, check_mode| {
+            encode_with(text, format, EncodeOptions { check_mode, ..EncodeOptions::default() }).map(|b| b.text)
+        };
+        let all = [CheckMode::Recompute, CheckMode::Require, CheckMode::Append];
+        for (format, data, good, bad) in [
+            (BarcodeFormat::Ean13, "590123412345", "5901234123457", "5901234123450"),
+            (BarcodeFormat::UpcA, "03600029145", "036000291452", "036000291459"),
+        ] {
+            // Missing or right: computed or kept, whatever the mode
+            for mode in all {
+                assert_eq!(with(data, format, mode).unwrap(), good);
+                assert_eq!(with(good, format, mode).unwrap(), good);
+            }
+            let expected = good.as_bytes()[good.len() - 1] - b'0';
+            let got = bad.as_bytes()[bad.len() - 1] - b'0';
+            assert_eq!(with(bad, format, CheckMode::Recompute).unwrap(), good);
+            assert_eq!(with(bad, format, CheckMode::Require), Err(EncodeError::BadCheckDigit { expected, got }));
+            assert_eq!(with(bad, format, CheckMode::Append).unwrap(), bad);
+        }
+    }
+
+    #[test]
+    fn price_check_digits() {
+        // 2875: 2- of 2 is 4, 2 of 8 is 6, 3 of 7 is 1, 5- of 5 is 3;
+        // (4 + 6 + 1 + 3) * 3 = 42
+        assert_eq!(price_check_digit(&[2, 8, 7, 5]), 2);
+        // 0199: 0 + 2 + 7 + 1 = 10, * 3 = 30
+        assert_eq!(price_check_digit(&[0, 1, 9, 9]), 0);
+        for d in 0..10u8 {
+            assert_eq!(PRICE_WEIGHT_2_MINUS[d as usize], ((d * 2) % 10 + 10 - (d * 2) / 10) % 10);
+            assert_eq!(PRICE_WEIGHT_5_MINUS[d as usize], ((d * 5) % 10 + 10 - (d * 5) / 10) % 10);
+        }
+    }
+
+    #[test]
+    fn variable_weight_codes() {
+        let code = variable_weight_upca("12345", "2875").unwrap();
+        assert_eq!(code, "212345228753");
+        assert_eq!(verify_check_digit(&code, BarcodeFormat::UpcA), Ok(true));
+        // Short prices are zero-padded to four digits
+        assert_eq!(variable_weight_upca("04312", "199").unwrap(), "204312001995");
+        assert!(encode(&code, BarcodeFormat::UpcA).is_ok());
+        assert_eq!(variable_weight_upca("1234", "100"), Err(EncodeError::WrongLength { got: 4, min: 5, max: 5 }));
+        assert_eq!(variable_weight_upca("12345", "10000"), Err(EncodeError::WrongLength { got: 5, min: 1, max: 4 }));
+        assert_eq!(variable_weight_upca("12345", ""), Err(EncodeError::WrongLength { got: 0, min: 1, max: 4 }));
+        assert_eq!(variable_weight_upca("12a45", "100"), Err(EncodeError::InvalidChar('a')));
+    }
+
+    #[test]
+    fn matrix2of5_reference_symbol() {
+        // Bars and spaces both carry the pattern, then a narrow space per digit
+        let expected = [
+            "111101010",  // start: extra-wide bar, narrow elements
+            "1110101110", // 1
+            "1000101110", // 2
+            "1110001010", // 3
+            "1011101110", // 4
+            "1110111010", // 5
+            "1000111010", // 6
+            "1010001110", // 7
+            "11110101",   // stop
+        ]
+        .concat();
+        let barcode = encode("1234567", BarcodeFormat::Matrix2of5).unwrap();
+        assert_eq!(symbol_bits(&barcode), expected);
+        assert!(encode("12-4", BarcodeFormat::Matrix2of5).is_err());
+    }
+
+    #[test]
+    fn code128_force_b() {
+        let with = |text: &str, code128_force_b| {
+            let options = EncodeOptions { code128_force_b, ..EncodeOptions::default() };
+            encode_with(text, BarcodeFormat::Code128, options).unwrap()
+        };
+        let (auto, forced) = (with("12345678", false), with("12345678", true));
+        assert_eq!(code128_values(&auto), [START_C, 12, 34, 56, 78, 47]);
+        let forced_values = code128_values(&forced);
+        assert_eq!(forced_values[0], START_B);
+        assert_eq!(forced_values[1..9], [17, 18, 19, 20, 21, 22, 23, 24]);
+        assert_eq!(forced.modules.len(), auto.modules.len() + 4 * 11);
+        for barcode in [&auto, &forced] {
+            assert_eq!(decode_code128(&barcode.modules).as_deref(), Some("12345678"));
+            assert_eq!(barcode.text, "12345678");
+        }
+        // Control characters still switch to subset A, but never to C
+        let values = code128_values(&with("\t1234", true));
+        assert_eq!(values[..2], [START_A, 73]);
+        assert!(!values.contains(&CODE_C));
+        assert_eq!(decode_code128(&with("\t1234", true).modules).as_deref(), Some("\t1234"));
+    }
+
+    #[test]
+    fn stripping_separators() {
+        assert_eq!(digits_only("0 36000 29145 2"), "036000291452");
+        assert_eq!(digits_only("590-1234-12345-7"), "5901234123457");
+        assert_eq!(digits_only("ISBN 978-0-306-40615-7"), "9780306406157");
+        assert_eq!(digits_only("no digits"), "");
+        assert!(!is_valid("0 36000 29145 2", BarcodeFormat::UpcA));
+        let barcode = encode(&digits_only("0 36000 29145 2"), BarcodeFormat::UpcA).unwrap();
+        assert_eq!(barcode.text, "036000291452");
+        let barcode = encode(&digits_only("590-1234-12345-7"), BarcodeFormat::Ean13).unwrap();
+        assert_eq!(barcode.text, "5901234123457");
+        // Only the digit-only formats are stripped
+        let numeric: Vec<BarcodeFormat> = BarcodeFormat::all().iter().copied().filter(|f| f.is_numeric()).collect();
+        assert!(!numeric.contains(&BarcodeFormat::Code128));
+        assert!(!numeric.contains(&BarcodeFormat::Code39));
+        assert!(!numeric.contains(&BarcodeFormat::Telepen));
+        assert!(numeric.contains(&BarcodeFormat::Ean13) && numeric.contains(&BarcodeFormat::UpcA));
+    }
+
+    fn pattern(bits: &str) -> [bool; 7] {
+        let mut out = [false; 7];
+        for (o, b) in out.iter_mut().zip(bits.bytes()) {
+            *o = b == b'1';
+        }
+        out
+    }
+
+    #[test]
+    fn ean_digit_tables() {
+        // Number set A, the L codes, as published by GS1
+        let l = [
+            "0001101", "0011001", "0010011", "0111101", "0100011",
+            "0110001", "0101111", "0111011", "0110111", "0001011",
+        ];
+        for d in 0..10 {
+            let lp = EAN_L_PATTERNS[d];
+            assert_eq!(lp, pattern(l[d]), "L {}", d);
+            // Two bars and two spaces, starting with a space and ending with a bar
+            let runs = lp.windows(2).filter(|w| w[0] != w[1]).count() + 1;
+            assert_eq!(runs, 4, "L {} element count", d);
+            assert!(!lp[0] && lp[6], "L {} edges", d);
+            for i in 0..7 {
+                assert_eq!(EAN_R_PATTERNS[d][i], !lp[i], "R {} is not the complement of L", d);
+                assert_eq!(EAN_G_PATTERNS[d][i], EAN_R_PATTERNS[d][6 - i], "G {} is not R reversed", d);
+            }
+        }
+    }
+
+    #[test]
+    fn ean_first_digit_parity() {
+        let rows = ["LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL", "LGGLGL"];
+        for (d, row) in rows.iter().enumerate() {
+            let expected: Vec<u8> = row.bytes().map(|b| (b == b'G') as u8).collect();
+            assert_eq!(EAN_PARITY[d].to_vec(), expected, "first digit {}", d);
+        }
+    }
+
+    #[test]
+    fn ean13_reference_symbol() {
+        // The widely published 5901234123457 example
+        let barcode = encode("5901234123457", BarcodeFormat::Ean13).unwrap();
+        assert_eq!(
+            symbol_bits(&barcode),
+            "10100010110100111011001100100110111101001110101010110011011011001000010101110010011101000100101",
+        );
+        assert_eq!(barcode.text, "5901234123457");
+    }
+}
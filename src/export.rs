@@ -0,0 +1,138 @@
+//! Tab-separated export rows for saved codes, `name\ttext\tformat\tcreated\tcategory`,
+//! as written to and read back from `barcode.codes:export`.
+//!
+//! Only the row syntax lives here; the app checks names and maps format keys.
+
+extern crate alloc;
+use alloc::string::String;
+
+/// One saved code as the fields of an export row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRow {
+    pub name: String,
+    pub text: String,
+    /// Format key as stored in the PDDB, e.g. `ean13`.
+    pub format: String,
+    /// Seconds since the Unix epoch when the code was saved, 0 if unknown.
+    pub created: u64,
+    /// Empty for no category.
+    pub category: String,
+}
+
+/// Format `row` as one line, without the newline.
+pub fn export_row(row: &ExportRow) -> String {
+    alloc::format!(
+        "{}\t{}\t{}\t{}\t{}",
+        tsv_escape(&row.name),
+        tsv_escape(&row.text),
+        tsv_escape(&row.format),
+        row.created,
+        tsv_escape(&row.category),
+    )
+}
+
+/// Parse a row written by `export_row`. `created` and the category may be
+/// left off, and an empty `created` reads as 0.
+pub fn parse_export_row(line: &str) -> Result<ExportRow, String> {
+    let mut fields = line.split('\t').map(tsv_unescape);
+    let name = fields.next().unwrap_or_default();
+    let text = fields.next().unwrap_or_default();
+    if text.is_empty() {
+        return Err(String::from("empty text"));
+    }
+    let format = fields.next().ok_or_else(|| String::from("missing format"))?;
+    let created = match fields.next() {
+        Some(c) if !c.is_empty() => c.parse().map_err(|_| alloc::format!("bad created time {:?}", c))?,
+        _ => 0,
+    };
+    let category = fields.next().unwrap_or_default();
+    Ok(ExportRow { name, text, format, created, category })
+}
+
+/// Backslash-escape the characters that would break a tab-separated row.
+pub fn tsv_escape(field: &str) -> String {
+    let mut out = String::new();
+    for c in field.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Undo `tsv_escape`. An unknown escape keeps its character.
+pub fn tsv_unescape(field: &str) -> String {
+    let mut out = String::new();
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(name: &str, text: &str, category: &str) -> ExportRow {
+        ExportRow {
+            name: String::from(name),
+            text: String::from(text),
+            format: String::from("code128"),
+            created: 1_700_000_000,
+            category: String::from(category),
+        }
+    }
+
+    #[test]
+    fn escape_round_trip() {
+        for field in ["plain", "a\tb", "two\nlines\r\n", "back\\slash", "\\t is not a tab", "trailing\\", ""] {
+            assert_eq!(tsv_unescape(&tsv_escape(field)), field);
+        }
+        assert_eq!(tsv_escape("a\tb\\c\n"), "a\\tb\\\\c\\n");
+    }
+
+    #[test]
+    fn row_round_trip() {
+        let rows = [
+            row("Shelf 4", "Hello, World!", ""),
+            row("tab\there", "line\nbreak", "back\\slash"),
+            row("x", "\\n\t\\\r", "cat\tegory\n"),
+        ];
+        for r in rows {
+            let line = export_row(&r);
+            assert_eq!(line.split('\t').count(), 5, "{:?}", line);
+            assert!(!line.contains('\n'));
+            assert_eq!(parse_export_row(&line), Ok(r));
+        }
+    }
+
+    #[test]
+    fn row_columns() {
+        assert_eq!(export_row(&row("a", "b", "c")), "a\tb\tcode128\t1700000000\tc");
+    }
+
+    #[test]
+    fn short_and_bad_rows() {
+        let parsed = parse_export_row("a\tb\tean13").unwrap();
+        assert_eq!((parsed.created, parsed.category.as_str()), (0, ""));
+        assert_eq!(parse_export_row("a\tb\tean13\t\tc").unwrap().created, 0);
+        assert!(parse_export_row("a\tb").is_err());
+        assert!(parse_export_row("a\t\tean13").is_err());
+        assert!(parse_export_row("a\tb\tean13\tyesterday").is_err());
+    }
+}
@@ -0,0 +1,12 @@
+//! Barcode encoder library for the Precursor Barcode Generator.
+//!
+//! Split out of the app binary so the encoders and the export row format
+//! build and test on the host without the Xous services:
+//! `cargo test --lib --no-default-features`. Cargo resolves the optional
+//! Xous path dependencies even with `app` off, so run that from the app's
+//! place in a xous-core checkout (`apps/barcode`).
+
+#![cfg_attr(target_os = "none", no_std)]
+
+pub mod barcode_encode;
+pub mod export;
@@ -1,17 +1,20 @@
 //! Precursor Barcode Generator
 //!
-//! Code 128, Code 39, EAN-13, UPC-A barcode generation on a 1-bit display.
+//! Code 128, Code 39, EAN-13, UPC-A, Telepen, Standard and Matrix 2 of 5,
+//! Code 32 and PZN barcode generation on a 1-bit display.
 //! Black bars on white — the display was born for this.
 
 #![cfg_attr(target_os = "none", no_std)]
 #![cfg_attr(target_os = "none", no_main)]
 
-
 mod app;
-mod barcode_encode;
+mod power;
 mod storage;
+mod strings;
 mod ui;
 
+use barcode::{barcode_encode, export};
+
 use app::BarcodeApp;
 use num_traits::FromPrimitive;
 use num_traits::ToPrimitive;
@@ -24,9 +27,33 @@ enum AppOp {
     Redraw = 0,
     Rawkeys = 1,
     FocusChange = 2,
+    /// One second of a scan test has passed; arg1 is its run number.
+    Tick = 3,
     Quit = 255,
 }
 
+/// Send `Tick(run)` to the app once a second, `secs` times, from a thread
+/// that then exits. A cancelled run's ticks are ignored by `BarcodeApp::tick`.
+fn start_ticker(sid: xous::SID, run: usize, secs: u8) {
+    let cid = match xous::connect(sid) {
+        Ok(cid) => cid,
+        Err(e) => {
+            log::warn!("Scan test ticker can't connect: {:?}", e);
+            return;
+        }
+    };
+    std::thread::spawn(move || {
+        let tt = ticktimer_server::Ticktimer::new().unwrap();
+        for _ in 0..secs {
+            tt.sleep_ms(1000).ok();
+            let tick = xous::Message::new_scalar(AppOp::Tick.to_usize().unwrap(), run, 0, 0, 0);
+            if xous::send_message(cid, tick).is_err() {
+                break;
+            }
+        }
+    });
+}
+
 fn main() -> ! {
     log_server::init_wait().unwrap();
     log::set_max_level(log::LevelFilter::Info);
@@ -58,9 +85,13 @@ fn main() -> ! {
 
     let mut app = BarcodeApp::new();
     app.init_storage();
+    match power::Power::new(&xns) {
+        Ok(power) => app.attach_power(power),
+        Err(e) => log::warn!("Backlight/suspend control unavailable: {:?}", e),
+    }
     let mut allow_redraw = true;
 
-    ui::draw(&app, &gam, content);
+    ui::draw(&app, &gam, content, screensize);
 
     loop {
         let msg = xous::receive_message(sid).unwrap();
@@ -68,7 +99,8 @@ fn main() -> ! {
             Some(AppOp::Redraw) => {
                 if allow_redraw {
                     app.needs_redraw = true;
-                    ui::draw(&app, &gam, content);
+                    app.redraw_region = app::RedrawRegion::Full;
+                    ui::draw(&app, &gam, content, screensize);
                 }
             }
             Some(AppOp::Rawkeys) => xous::msg_scalar_unpack!(msg, k1, k2, k3, k4, {
@@ -92,8 +124,19 @@ fn main() -> ! {
 
                 if should_quit { break; }
 
+                if let Some((run, secs)) = app.take_ticker_request() {
+                    start_ticker(sid, run, secs);
+                }
+
+                if app.needs_redraw && allow_redraw {
+                    ui::draw(&app, &gam, content, screensize);
+                    app.needs_redraw = false;
+                }
+            }),
+            Some(AppOp::Tick) => xous::msg_scalar_unpack!(msg, run, _, _, _, {
+                app.tick(run);
                 if app.needs_redraw && allow_redraw {
-                    ui::draw(&app, &gam, content);
+                    ui::draw(&app, &gam, content, screensize);
                     app.needs_redraw = false;
                 }
             }),
@@ -101,11 +144,14 @@ fn main() -> ! {
                 match gam::FocusState::convert_focus_change(state_code) {
                     gam::FocusState::Background => {
                         allow_redraw = false;
+                        app.set_foreground(false);
                         app.save_state();
                     }
                     gam::FocusState::Foreground => {
                         allow_redraw = true;
-                        ui::draw(&app, &gam, content);
+                        app.set_foreground(true);
+                        app.redraw_region = app::RedrawRegion::Full;
+                        ui::draw(&app, &gam, content, screensize);
                     }
                 }
             }),
@@ -114,6 +160,8 @@ fn main() -> ! {
         }
     }
 
+    // Never exit holding the suspend inhibit
+    app.set_foreground(false);
     app.save_state();
     xns.unregister_server(sid).unwrap();
     xous::destroy_server(sid).unwrap();
@@ -0,0 +1,55 @@
+//! Backlight and suspend control while a barcode is on screen.
+//!
+//! Scanning can take a few seconds; the code must stay lit and the device
+//! awake for the whole time, then go back to normal power management.
+
+/// Full brightness for both backlight channels.
+const BACKLIGHT_MAX: u8 = 255;
+/// Level restored after a boost. COM can't report the current backlight,
+/// so the level in effect before the boost is taken to be the system default.
+const BACKLIGHT_NORMAL: u8 = 191;
+
+pub struct Power {
+    com: com::Com,
+    susres: susres::Susres,
+    awake: bool,
+    boosted: bool,
+    normal: (u8, u8),
+}
+
+impl Power {
+    pub fn new(xns: &xous_names::XousNames) -> Result<Self, xous::Error> {
+        let com = com::Com::new(xns)?;
+        let susres = susres::Susres::new_without_hook(xns)?;
+        Ok(Self {
+            com,
+            susres,
+            awake: false,
+            boosted: false,
+            normal: (BACKLIGHT_NORMAL, BACKLIGHT_NORMAL),
+        })
+    }
+
+    /// Block suspend while `on`; release otherwise.
+    pub fn keep_awake(&mut self, on: bool) {
+        if on == self.awake {
+            return;
+        }
+        if let Err(e) = self.susres.set_suspendable(!on) {
+            log::warn!("Failed to set suspendable={}: {:?}", !on, e);
+        }
+        self.awake = on;
+    }
+
+    /// Hold the backlight at full while `on`; restore the normal level otherwise.
+    pub fn boost_backlight(&mut self, on: bool) {
+        if on == self.boosted {
+            return;
+        }
+        let (main, secondary) = if on { (BACKLIGHT_MAX, BACKLIGHT_MAX) } else { self.normal };
+        if let Err(e) = self.com.set_backlight(main, secondary) {
+            log::warn!("Failed to set backlight: {:?}", e);
+        }
+        self.boosted = on;
+    }
+}
@@ -4,13 +4,203 @@ extern crate alloc;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use crate::app::{BarcodeSettings, SavedBarcode};
-use crate::barcode_encode::BarcodeFormat;
+use crate::app::{BarcodeSettings, SavedBarcode, Snapshot, StartScreen, TextSize};
+use crate::barcode_encode::{self, BarcodeFormat, CheckMode};
+use crate::strings::{Language, Str};
 
 const DICT_SETTINGS: &str = "barcode.settings";
 const DICT_CODES: &str = "barcode.codes";
 const KEY_CONFIG: &str = "config";
+const KEY_LAST: &str = "last";
 const KEY_INDEX: &str = "index";
+/// Text key of `name,gtin` lines to add to the saved list, put there from outside the app.
+const KEY_IMPORT: &str = "import";
+/// Text key the saved list is exported to, one tab-separated row per code.
+const KEY_EXPORT: &str = "export";
+
+/// Name a format is stored under in the settings and code records.
+pub fn format_key(format: BarcodeFormat) -> &'static str {
+    match format {
+        BarcodeFormat::Code128 => "code128",
+        BarcodeFormat::Code39 => "code39",
+        BarcodeFormat::Ean13 => "ean13",
+        BarcodeFormat::UpcA => "upca",
+        BarcodeFormat::Telepen => "telepen",
+        BarcodeFormat::Std2of5 => "std2of5",
+        BarcodeFormat::Matrix2of5 => "matrix2of5",
+        BarcodeFormat::Code32 => "code32",
+        BarcodeFormat::Pzn => "pzn",
+    }
+}
+
+/// Unknown or missing names fall back to Code 128.
+fn format_from_key(key: Option<&str>) -> BarcodeFormat {
+    key.and_then(format_by_key).unwrap_or(BarcodeFormat::Code128)
+}
+
+/// Format stored under `key`, if any.
+pub fn format_by_key(key: &str) -> Option<BarcodeFormat> {
+    BarcodeFormat::all().iter().copied().find(|&f| format_key(f) == key)
+}
+
+fn check_mode_key(mode: CheckMode) -> &'static str {
+    match mode {
+        CheckMode::Recompute => "recompute",
+        CheckMode::Require => "require",
+        CheckMode::Append => "append",
+    }
+}
+
+/// Unknown names fall back to `default`.
+fn check_mode_from_key(key: Option<&str>, default: CheckMode) -> CheckMode {
+    [CheckMode::Recompute, CheckMode::Require, CheckMode::Append]
+        .into_iter()
+        .find(|&m| Some(check_mode_key(m)) == key)
+        .unwrap_or(default)
+}
+
+/// Settings from a `barcode.settings:config` record. `None`, for the
+/// defaults, when it isn't a JSON object; fields that are missing, of the
+/// wrong type or out of range get their default or are clamped.
+pub fn parse_settings(data: &[u8]) -> Option<BarcodeSettings> {
+    let json = match serde_json::from_slice::<serde_json::Value>(data) {
+        Ok(json) if json.is_object() => json,
+        Ok(_) => {
+            log::warn!("Settings record isn't an object, using defaults");
+            return None;
+        }
+        Err(e) => {
+            log::warn!("Corrupt settings record, using defaults: {:?}", e);
+            return None;
+        }
+    };
+
+    let format = format_from_key(json.get("format").and_then(|v| v.as_str()));
+    let bar_width = json.get("bar_width").and_then(|v| v.as_u64()).unwrap_or(2).clamp(1, 4) as u8;
+    let bar_height = json.get("bar_height").and_then(|v| v.as_u64()).unwrap_or(200).clamp(80, 300) as u16;
+    let auto_height = json.get("auto_height").and_then(|v| v.as_bool()).unwrap_or(false);
+    let text_size = json
+        .get("text_size")
+        .and_then(|v| v.as_str())
+        .and_then(TextSize::from_code)
+        .unwrap_or(TextSize::Normal);
+    let auto_format = json.get("auto_format").and_then(|v| v.as_bool()).unwrap_or(true);
+    let trim_input = json.get("trim_input").and_then(|v| v.as_bool()).unwrap_or(true);
+    let strip_nondigits = json.get("strip_nondigits").and_then(|v| v.as_bool()).unwrap_or(false);
+    let max_brightness_on_display =
+        json.get("max_brightness_on_display").and_then(|v| v.as_bool()).unwrap_or(true);
+    let scan_test_secs = json.get("scan_test_secs").and_then(|v| v.as_u64()).unwrap_or(10).clamp(5, 60) as u8;
+    let code39_preserve_case = json.get("code39_preserve_case").and_then(|v| v.as_bool()).unwrap_or(false);
+    let std2of5_check = json.get("std2of5_check").and_then(|v| v.as_bool()).unwrap_or(false);
+    let show_check_char = json.get("show_check_char").and_then(|v| v.as_bool()).unwrap_or(false);
+    let ean_zero_pad = json.get("ean_zero_pad").and_then(|v| v.as_bool()).unwrap_or(false);
+    let upc_text = json.get("upc_text").and_then(|v| v.as_bool()).unwrap_or(false);
+    let wide_ratio = json
+        .get("wide_ratio")
+        .and_then(|v| v.as_u64())
+        .map(|v| v.clamp(2, 3) as u8)
+        .unwrap_or(barcode_encode::DEFAULT_WIDE_RATIO);
+    let code39_gap = json
+        .get("code39_gap")
+        .and_then(|v| v.as_u64())
+        .map(|v| v.clamp(1, 3) as u8)
+        .unwrap_or(barcode_encode::DEFAULT_CODE39_GAP);
+    // Older settings only had the on/off strict check
+    let strict = json.get("strict_check_digit").and_then(|v| v.as_bool()).unwrap_or(false);
+    let check_digit_mode = check_mode_from_key(
+        json.get("check_digit_mode").and_then(|v| v.as_str()),
+        if strict { CheckMode::Require } else { CheckMode::Recompute },
+    );
+    let append_enter = json.get("append_enter").and_then(|v| v.as_bool()).unwrap_or(false);
+    let code128_force_b = json.get("code128_force_b").and_then(|v| v.as_bool()).unwrap_or(false);
+    let code128_latin1 = json.get("code128_latin1").and_then(|v| v.as_bool()).unwrap_or(false);
+    let resume_last = json.get("resume_last").and_then(|v| v.as_bool()).unwrap_or(false);
+    let autosave = json.get("autosave").and_then(|v| v.as_bool()).unwrap_or(false);
+    let private_mode = json.get("private_mode").and_then(|v| v.as_bool()).unwrap_or(false);
+    let start_screen = json
+        .get("start_screen")
+        .and_then(|v| v.as_str())
+        .and_then(StartScreen::from_code)
+        .unwrap_or(StartScreen::Menu);
+    let mirror = json.get("mirror").and_then(|v| v.as_bool()).unwrap_or(false);
+    let center_bars = json.get("center_bars").and_then(|v| v.as_bool()).unwrap_or(false);
+    let draw_frame = json.get("draw_frame").and_then(|v| v.as_bool()).unwrap_or(false);
+    let tile_count = json.get("tile_count").and_then(|v| v.as_u64()).unwrap_or(4).clamp(2, 12) as u8;
+    let key_repeat = json.get("key_repeat").and_then(|v| v.as_bool()).unwrap_or(false);
+    let prefix = json.get("prefix").and_then(|v| v.as_str()).map(String::from).unwrap_or_default();
+    let suffix = json.get("suffix").and_then(|v| v.as_str()).map(String::from).unwrap_or_default();
+    let language = json.get("language").and_then(|v| v.as_str()).and_then(Language::from_code).unwrap_or(Language::En);
+
+    Some(BarcodeSettings {
+        format,
+        bar_width,
+        bar_height,
+        auto_height,
+        text_size,
+        auto_format,
+        trim_input,
+        strip_nondigits,
+        max_brightness_on_display,
+        scan_test_secs,
+        code39_preserve_case,
+        std2of5_check,
+        show_check_char,
+        ean_zero_pad,
+        upc_text,
+        wide_ratio,
+        code39_gap,
+        check_digit_mode,
+        append_enter,
+        code128_force_b,
+        code128_latin1,
+        resume_last,
+        autosave,
+        private_mode,
+        start_screen,
+        mirror,
+        center_bars,
+        draw_frame,
+        tile_count,
+        key_repeat,
+        prefix,
+        suffix,
+        language,
+    })
+}
+
+/// Saved code `name` from its `barcode.codes:code.{name}` record, or `None`
+/// when the record is unreadable or has no text.
+pub fn parse_code(name: &str, data: &[u8]) -> Option<SavedBarcode> {
+    let json: serde_json::Value = serde_json::from_slice(data).ok()?;
+
+    let text = json.get("text").and_then(|v| v.as_str()).filter(|t| !t.is_empty())?;
+    let format = format_from_key(json.get("format").and_then(|v| v.as_str()));
+    let category = json.get("category").and_then(|v| v.as_str()).map(String::from);
+    let created = json.get("created").and_then(|v| v.as_u64()).unwrap_or(0);
+    Some(SavedBarcode { name: String::from(name), text: String::from(text), format, category, created })
+}
+
+/// Why a write to the PDDB didn't happen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageError {
+    /// No PDDB to write to: it never mounted, so the app has no `Storage`.
+    NotMounted,
+    /// A key couldn't be opened, written or synced.
+    Write,
+    /// The record couldn't be turned into JSON.
+    Serialize,
+}
+
+impl StorageError {
+    /// What to tell the user.
+    pub fn message(self) -> Str {
+        match self {
+            StorageError::NotMounted => Str::SaveFailedUnmounted,
+            StorageError::Write => Str::SaveFailedWrite,
+            StorageError::Serialize => Str::SaveFailedSerialize,
+        }
+    }
+}
 
 pub struct Storage {
     pddb: pddb::Pddb,
@@ -23,122 +213,273 @@ impl Storage {
         Ok(Self { pddb })
     }
 
+    /// Stored settings, or `None` if there are none or they can't be read.
+    /// Fields that are missing or out of range fall back to their defaults.
     pub fn load_settings(&mut self) -> Option<BarcodeSettings> {
         let mut key = self.pddb.get(DICT_SETTINGS, KEY_CONFIG, None, false, false, None, None::<fn()>).ok()?;
         let mut buf = Vec::new();
         use std::io::Read;
-        key.read_to_end(&mut buf).ok()?;
-        let json: serde_json::Value = serde_json::from_slice(&buf).ok()?;
-
-        let format = match json.get("format").and_then(|v| v.as_str()) {
-            Some("code39") => BarcodeFormat::Code39,
-            Some("ean13") => BarcodeFormat::Ean13,
-            Some("upca") => BarcodeFormat::UpcA,
-            _ => BarcodeFormat::Code128,
-        };
-        let bar_width = json.get("bar_width").and_then(|v| v.as_u64()).unwrap_or(2) as u8;
-        let bar_height = json.get("bar_height").and_then(|v| v.as_u64()).unwrap_or(200) as u16;
-        let auto_format = json.get("auto_format").and_then(|v| v.as_bool()).unwrap_or(true);
-
-        Some(BarcodeSettings { format, bar_width, bar_height, auto_format })
+        if let Err(e) = key.read_to_end(&mut buf) {
+            log::warn!("Can't read settings, using defaults: {:?}", e);
+            return None;
+        }
+        parse_settings(&buf)
     }
 
-    pub fn save_settings(&mut self, settings: &BarcodeSettings) {
-        let fmt_str = match settings.format {
-            BarcodeFormat::Code128 => "code128",
-            BarcodeFormat::Code39 => "code39",
-            BarcodeFormat::Ean13 => "ean13",
-            BarcodeFormat::UpcA => "upca",
-        };
+    pub fn save_settings(&mut self, settings: &BarcodeSettings) -> Result<(), StorageError> {
+        let fmt_str = format_key(settings.format);
         let json = serde_json::json!({
             "format": fmt_str,
             "bar_width": settings.bar_width,
             "bar_height": settings.bar_height,
+            "auto_height": settings.auto_height,
+            "text_size": settings.text_size.code(),
             "auto_format": settings.auto_format,
+            "trim_input": settings.trim_input,
+            "strip_nondigits": settings.strip_nondigits,
+            "max_brightness_on_display": settings.max_brightness_on_display,
+            "scan_test_secs": settings.scan_test_secs,
+            "code39_preserve_case": settings.code39_preserve_case,
+            "std2of5_check": settings.std2of5_check,
+            "show_check_char": settings.show_check_char,
+            "ean_zero_pad": settings.ean_zero_pad,
+            "upc_text": settings.upc_text,
+            "wide_ratio": settings.wide_ratio,
+            "code39_gap": settings.code39_gap,
+            "check_digit_mode": check_mode_key(settings.check_digit_mode),
+            "append_enter": settings.append_enter,
+            "code128_force_b": settings.code128_force_b,
+            "code128_latin1": settings.code128_latin1,
+            "resume_last": settings.resume_last,
+            "autosave": settings.autosave,
+            "private_mode": settings.private_mode,
+            "start_screen": settings.start_screen.code(),
+            "mirror": settings.mirror,
+            "center_bars": settings.center_bars,
+            "draw_frame": settings.draw_frame,
+            "tile_count": settings.tile_count,
+            "key_repeat": settings.key_repeat,
+            "prefix": settings.prefix,
+            "suffix": settings.suffix,
+            "language": settings.language.code(),
         });
-        let data = serde_json::to_vec(&json).unwrap_or_default();
+        let data = serde_json::to_vec(&json).map_err(|_| StorageError::Serialize)?;
+        self.write_key(DICT_SETTINGS, KEY_CONFIG, &data)?;
+        self.sync()
+    }
 
-        if let Ok(mut key) = self.pddb.get(DICT_SETTINGS, KEY_CONFIG, None, true, true, Some(data.len()), None::<fn()>) {
-            use std::io::{Seek, Write};
-            key.seek(std::io::SeekFrom::Start(0)).ok();
-            key.write_all(&data).ok();
-            key.set_len(data.len() as u64).ok();
-        }
+    /// Where the app was when it last went away, if recorded. Records from
+    /// before the screen was kept only have the barcode.
+    pub fn load_last(&mut self) -> Option<Snapshot> {
+        let mut key = self.pddb.get(DICT_SETTINGS, KEY_LAST, None, false, false, None, None::<fn()>).ok()?;
+        let mut buf = Vec::new();
+        use std::io::Read;
+        key.read_to_end(&mut buf).ok()?;
+        let json: serde_json::Value = serde_json::from_slice(&buf).ok()?;
+
+        let shown = json.get("text").and_then(|v| v.as_str()).map(|text| {
+            (String::from(text), format_from_key(json.get("format").and_then(|v| v.as_str())))
+        });
+        let screen = json
+            .get("screen")
+            .and_then(|v| v.as_str())
+            .and_then(StartScreen::from_code)
+            .unwrap_or(StartScreen::Menu);
+        let input_text = json.get("input").and_then(|v| v.as_str()).unwrap_or("");
+        let load_index = json.get("load_index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        Some(Snapshot { screen, input_text: String::from(input_text), load_index, shown })
+    }
+
+    /// Record the screen, input and any barcode shown.
+    pub fn save_last(&mut self, last: &Snapshot) -> Result<(), StorageError> {
+        let json = match last.shown {
+            Some((ref text, format)) => serde_json::json!({
+                "screen": last.screen.code(),
+                "input": last.input_text,
+                "load_index": last.load_index,
+                "text": text,
+                "format": format_key(format),
+            }),
+            None => serde_json::json!({
+                "screen": last.screen.code(),
+                "input": last.input_text,
+                "load_index": last.load_index,
+            }),
+        };
+        let data = serde_json::to_vec(&json).map_err(|_| StorageError::Serialize)?;
+        self.write_key(DICT_SETTINGS, KEY_LAST, &data)?;
+        self.sync()
+    }
+
+    /// Contents of the import key, if there is one.
+    pub fn load_import(&mut self) -> Option<String> {
+        let mut key = self.pddb.get(DICT_CODES, KEY_IMPORT, None, false, false, None, None::<fn()>).ok()?;
+        let mut buf = Vec::new();
+        use std::io::Read;
+        key.read_to_end(&mut buf).ok()?;
+        String::from_utf8(buf).ok()
+    }
+
+    /// Remove the import key once its lines have been taken in.
+    pub fn clear_import(&mut self) {
+        self.pddb.delete_key(DICT_CODES, KEY_IMPORT, None).ok();
         self.pddb.sync().ok();
     }
 
-    pub fn load_codes(&mut self) -> Vec<SavedBarcode> {
-        let mut codes = Vec::new();
+    /// Replace the export key with `text`.
+    pub fn save_export(&mut self, text: &str) -> Result<(), StorageError> {
+        self.write_key(DICT_CODES, KEY_EXPORT, text.as_bytes())?;
+        self.sync()
+    }
 
-        let names: Vec<String> = match self.pddb.get(DICT_CODES, KEY_INDEX, None, false, false, None, None::<fn()>) {
+    /// Read just the names from the index, without parsing any code records.
+    pub fn load_index(&mut self) -> Vec<String> {
+        match self.pddb.get(DICT_CODES, KEY_INDEX, None, false, false, None, None::<fn()>) {
             Ok(mut key) => {
                 let mut buf = Vec::new();
                 use std::io::Read;
                 if key.read_to_end(&mut buf).is_ok() {
-                    serde_json::from_slice(&buf).unwrap_or_default()
+                    serde_json::from_slice(&buf).unwrap_or_else(|e| {
+                        log::warn!("Corrupt saved code index: {:?}", e);
+                        Vec::new()
+                    })
                 } else {
                     Vec::new()
                 }
             }
             Err(_) => Vec::new(),
-        };
+        }
+    }
 
+    pub fn load_codes(&mut self) -> Vec<SavedBarcode> {
+        let mut codes = Vec::new();
+
+        let names = self.load_index();
         for name in &names {
-            let key_name = alloc::format!("code.{}", name);
-            if let Ok(mut key) = self.pddb.get(DICT_CODES, &key_name, None, false, false, None, None::<fn()>) {
-                let mut buf = Vec::new();
-                use std::io::Read;
-                if key.read_to_end(&mut buf).is_ok() {
-                    if let Ok(json) = serde_json::from_slice::<serde_json::Value>(&buf) {
-                        let text = json.get("text").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                        let format = match json.get("format").and_then(|v| v.as_str()) {
-                            Some("code39") => BarcodeFormat::Code39,
-                            Some("ean13") => BarcodeFormat::Ean13,
-                            Some("upca") => BarcodeFormat::UpcA,
-                            _ => BarcodeFormat::Code128,
-                        };
-                        codes.push(SavedBarcode { name: name.clone(), text: String::from(text), format });
-                    }
-                }
+            // One bad record shouldn't cost the rest of the list
+            match self.load_code(name) {
+                Some(code) => codes.push(code),
+                None => log::warn!("Skipping unreadable saved code {:?}", name),
             }
         }
 
         codes
     }
 
-    pub fn save_codes(&mut self, codes: &[SavedBarcode]) {
-        let names: Vec<&str> = codes.iter().map(|c| c.name.as_str()).collect();
-        let index_data = serde_json::to_vec(&names).unwrap_or_default();
+    fn load_code(&mut self, name: &str) -> Option<SavedBarcode> {
+        let key_name = alloc::format!("code.{}", name);
+        let mut key = self.pddb.get(DICT_CODES, &key_name, None, false, false, None, None::<fn()>).ok()?;
+        let mut buf = Vec::new();
+        use std::io::Read;
+        key.read_to_end(&mut buf).ok()?;
+        parse_code(name, &buf)
+    }
 
-        if let Ok(mut key) = self.pddb.get(DICT_CODES, KEY_INDEX, None, true, true, Some(index_data.len()), None::<fn()>) {
-            use std::io::{Seek, Write};
-            key.seek(std::io::SeekFrom::Start(0)).ok();
-            key.write_all(&index_data).ok();
-            key.set_len(index_data.len() as u64).ok();
-        }
+    /// Write all codes and the index. A record that fails doesn't stop the
+    /// rest from being written; the first error is returned.
+    pub fn save_codes(&mut self, codes: &[SavedBarcode]) -> Result<(), StorageError> {
+        let names: Vec<&str> = codes.iter().map(|c| c.name.as_str()).collect();
+        let index_data = serde_json::to_vec(&names).map_err(|_| StorageError::Serialize)?;
+        let mut result = self.write_key(DICT_CODES, KEY_INDEX, &index_data);
 
         for code in codes {
             let key_name = alloc::format!("code.{}", code.name);
-            let fmt_str = match code.format {
-                BarcodeFormat::Code128 => "code128",
-                BarcodeFormat::Code39 => "code39",
-                BarcodeFormat::Ean13 => "ean13",
-                BarcodeFormat::UpcA => "upca",
-            };
+            let fmt_str = format_key(code.format);
             let json = serde_json::json!({
                 "text": code.text,
                 "format": fmt_str,
+                "category": code.category,
+                "created": code.created,
             });
-            let data = serde_json::to_vec(&json).unwrap_or_default();
-
-            if let Ok(mut key) = self.pddb.get(DICT_CODES, &key_name, None, true, true, Some(data.len()), None::<fn()>) {
-                use std::io::{Seek, Write};
-                key.seek(std::io::SeekFrom::Start(0)).ok();
-                key.write_all(&data).ok();
-                key.set_len(data.len() as u64).ok();
+            let written = serde_json::to_vec(&json)
+                .map_err(|_| StorageError::Serialize)
+                .and_then(|data| self.write_key(DICT_CODES, &key_name, &data));
+            if let Err(e) = written {
+                log::warn!("Failed to save code {:?}: {:?}", code.name, e);
+                result = result.and(Err(e));
             }
         }
 
-        self.pddb.sync().ok();
+        result.and(self.sync())
+    }
+
+    /// Replace the contents of `dict`/`key` with `data`.
+    fn write_key(&mut self, dict: &str, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        use std::io::{Seek, Write};
+        let mut handle = self.pddb.get(dict, key, None, true, true, Some(data.len()), None::<fn()>)
+            .map_err(|_| StorageError::Write)?;
+        handle.seek(std::io::SeekFrom::Start(0)).map_err(|_| StorageError::Write)?;
+        handle.write_all(data).map_err(|_| StorageError::Write)?;
+        handle.set_len(data.len() as u64).map_err(|_| StorageError::Write)?;
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), StorageError> {
+        self.pddb.sync().map_err(|_| StorageError::Write)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_fields() {
+        let s = parse_settings(br#"{"format": "ean13", "bar_width": 3, "bar_height": 150, "key_repeat": true}"#).unwrap();
+        assert_eq!(s.format, BarcodeFormat::Ean13);
+        assert_eq!((s.bar_width, s.bar_height), (3, 150));
+        assert!(s.key_repeat);
+    }
+
+    #[test]
+    fn truncated_settings_use_defaults() {
+        assert!(parse_settings(br#"{"format": "ean13", "bar_wid"#).is_none());
+        assert!(parse_settings(b"").is_none());
+        assert!(parse_settings(&[0xff, 0xfe, b'{']).is_none());
+    }
+
+    #[test]
+    fn non_object_settings_use_defaults() {
+        for data in [&b"[1, 2, 3]"[..], b"\"config\"", b"42", b"null"] {
+            assert!(parse_settings(data).is_none());
+        }
+    }
+
+    #[test]
+    fn out_of_range_settings_are_clamped() {
+        let s = parse_settings(
+            br#"{"bar_width": 99, "bar_height": 5, "scan_test_secs": 0, "tile_count": 1000,
+                "wide_ratio": 7, "code39_gap": 0}"#,
+        )
+        .unwrap();
+        assert_eq!((s.bar_width, s.bar_height), (4, 80));
+        assert_eq!((s.scan_test_secs, s.tile_count), (5, 12));
+        assert_eq!((s.wide_ratio, s.code39_gap), (3, 1));
+    }
+
+    #[test]
+    fn wrong_types_and_unknown_names_use_defaults() {
+        let s = parse_settings(
+            br#"{"format": "qr", "bar_width": "wide", "bar_height": -3, "text_size": 2,
+                "language": "xx", "check_digit_mode": "maybe", "auto_format": "yes"}"#,
+        )
+        .unwrap();
+        let d = BarcodeSettings::default();
+        assert_eq!(s.format, BarcodeFormat::Code128);
+        assert_eq!((s.bar_width, s.bar_height), (2, 200));
+        assert_eq!(s.text_size, d.text_size);
+        assert_eq!(s.language, d.language);
+        assert_eq!(s.check_digit_mode, CheckMode::Recompute);
+        assert!(s.auto_format);
+    }
+
+    #[test]
+    fn corrupt_code_records_are_skipped() {
+        let code = parse_code("shelf", br#"{"text": "4006381333931", "format": "ean13", "created": 1700000000}"#).unwrap();
+        assert_eq!((code.text.as_str(), code.format, code.created), ("4006381333931", BarcodeFormat::Ean13, 1700000000));
+        assert!(parse_code("shelf", br#"{"text": "40063"#).is_none());
+        assert!(parse_code("shelf", br#"{"text": ""}"#).is_none());
+        assert!(parse_code("shelf", br#"{"format": "ean13"}"#).is_none());
+        assert!(parse_code("shelf", b"[]").is_none());
     }
 }
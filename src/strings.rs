@@ -0,0 +1,605 @@
+//! User-facing UI strings for the Barcode Generator, per language.
+//!
+//! Format names ("Code 128", "EAN-13") are technical labels and stay in
+//! `BarcodeFormat::label`, untranslated.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    En,
+    De,
+}
+
+impl Language {
+    /// Name of the language in that language, for the settings screen.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::En => "English",
+            Language::De => "Deutsch",
+        }
+    }
+
+    /// Short code used in the settings JSON.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::De => "de",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Language> {
+        match code {
+            "en" => Some(Language::En),
+            "de" => Some(Language::De),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> Language {
+        match self {
+            Language::En => Language::De,
+            Language::De => Language::En,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Str {
+    AppTitle,
+    SavedBarcodesCount,
+    LastCode,
+    ShowAgainHint,
+    // Menu
+    MenuNewBarcode,
+    MenuComposeGtin,
+    MenuPriceCode,
+    MenuSavedCodes,
+    MenuSelfTest,
+    MenuSettings,
+    MenuHelp,
+    // Settings
+    SettingFormat,
+    SettingAutoDetect,
+    SettingBarWidth,
+    SettingBarHeight,
+    SettingAutoHeight,
+    SettingTrimInput,
+    SettingStripNonDigits,
+    SettingMaxBrightness,
+    SettingScanTestTime,
+    SettingCode39Case,
+    SettingStd2of5Check,
+    SettingShowCheckChar,
+    SettingEanZeroPad,
+    SettingUpcText,
+    SettingWideRatio,
+    SettingCode39Gap,
+    SettingCheckMode,
+    CheckRecompute,
+    CheckRequire,
+    CheckAppend,
+    SettingAppendEnter,
+    SettingCode128ForceB,
+    SettingCode128Latin1,
+    SettingResumeLast,
+    SettingAutoSave,
+    SettingPrivateMode,
+    PrivateTag,
+    SettingMirror,
+    SettingCenterBars,
+    SettingDrawFrame,
+    SettingTileCount,
+    SettingKeyRepeat,
+    SettingPrefix,
+    SettingSuffix,
+    SettingLanguage,
+    SettingStartScreen,
+    StartMenu,
+    SettingTextSize,
+    TextSmall,
+    TextNormal,
+    TextLarge,
+    SettingsHint,
+    On,
+    Off,
+    // Input
+    EnterTextTitle,
+    InputHint,
+    InfoChars,
+    InfoLength,
+    InfoCheck,
+    InfoExample,
+    InfoHint,
+    Empty,
+    Format,
+    Auto,
+    Or,
+    Wide,
+    Tall,
+    TrimmedFrom,
+    Valid,
+    Invalid,
+    InputNotValid,
+    CheckDigit,
+    CheckDigitOk,
+    DigitsOnly,
+    CheckDigitAdded,
+    CheckDigitNeedsDigits,
+    MaxLength,
+    NeedsExtended39,
+    // Display
+    DisplayHint,
+    ScanOk,
+    ScanThin,
+    ScanClipped,
+    SelfTest,
+    SelfTestHint,
+    SavedBrowseHint,
+    TooWide,
+    Tiles,
+    TileHint,
+    Verified,
+    Mismatch,
+    VerifyUnavailable,
+    NotUpcA,
+    // Saving
+    SavePrompt,
+    AlreadySavedAs,
+    SaveAnyway,
+    DiscardPrompt,
+    KeypadTitle,
+    KeypadDelete,
+    KeypadDone,
+    KeypadHint,
+    ScanTest,
+    ScanTestHint,
+    SaveTitle,
+    EnterName,
+    TypeName,
+    SaveNameHint,
+    SavedAs,
+    NameBlank,
+    NothingToImport,
+    Imported,
+    Exported,
+    SkippedLines,
+    AutoSavedAs,
+    PurgedAutoSaved,
+    SaveFailedWrite,
+    SaveFailedUnmounted,
+    SaveFailedSerialize,
+    // Saved codes
+    SavedTitle,
+    NoSavedCodes,
+    SavedListHint,
+    NothingToUndo,
+    CategoryTitle,
+    EnterCategory,
+    NoCategory,
+    CategoryHint,
+    PrefixTitle,
+    SuffixTitle,
+    EnterAffix,
+    AffixNone,
+    // Compose GTIN
+    ComposeTitle,
+    EnterPrefix,
+    EnterItemRef,
+    TypeDigits,
+    ComposeHint,
+    ComposeLength,
+    PriceTitle,
+    EnterItemNumber,
+    EnterPrice,
+    HelpTitle,
+}
+
+impl Str {
+    pub fn get(self, lang: Language) -> &'static str {
+        match lang {
+            Language::En => self.en(),
+            Language::De => self.de(),
+        }
+    }
+
+    fn en(self) -> &'static str {
+        match self {
+            Str::AppTitle => "Barcode Generator",
+            Str::SavedBarcodesCount => "saved barcodes",
+            Str::LastCode => "Last",
+            Str::ShowAgainHint => "R: show again",
+            Str::MenuNewBarcode => "New Barcode",
+            Str::MenuComposeGtin => "Compose GTIN",
+            Str::MenuPriceCode => "Price Code (UPC 2)",
+            Str::MenuSavedCodes => "Saved Codes",
+            Str::MenuSelfTest => "Self Test",
+            Str::MenuSettings => "Settings",
+            Str::MenuHelp => "Help",
+            Str::SettingFormat => "Format",
+            Str::SettingAutoDetect => "Auto-Detect",
+            Str::SettingBarWidth => "Bar Width",
+            Str::SettingBarHeight => "Bar Height",
+            Str::SettingAutoHeight => "Auto Height",
+            Str::SettingTrimInput => "Trim Input",
+            Str::SettingStripNonDigits => "Strip Non-Digits",
+            Str::SettingMaxBrightness => "Max Brightness",
+            Str::SettingScanTestTime => "Scan Test Time",
+            Str::SettingCode39Case => "C39 Keep Case",
+            Str::SettingStd2of5Check => "2 of 5 Check",
+            Str::SettingShowCheckChar => "Show Check Char",
+            Str::SettingEanZeroPad => "EAN Zero Pad",
+            Str::SettingUpcText => "EAN 0 as UPC Text",
+            Str::SettingWideRatio => "Wide Ratio",
+            Str::SettingCode39Gap => "Code 39 Gap",
+            Str::SettingCheckMode => "Check Digit",
+            Str::CheckRecompute => "Recompute",
+            Str::CheckRequire => "Require",
+            Str::CheckAppend => "Keep typed",
+            Str::SettingAppendEnter => "C128 Add Enter",
+            Str::SettingCode128ForceB => "C128 Subset B",
+            Str::SettingCode128Latin1 => "C128 Latin-1",
+            Str::SettingResumeLast => "Resume Last",
+            Str::SettingAutoSave => "Auto-Save",
+            Str::SettingPrivateMode => "Private Mode",
+            Str::PrivateTag => "PRIVATE",
+            Str::SettingMirror => "Mirror",
+            Str::SettingCenterBars => "Center on Bars",
+            Str::SettingDrawFrame => "Draw Frame",
+            Str::SettingTileCount => "Print Copies",
+            Str::SettingKeyRepeat => "Fast Repeat",
+            Str::SettingPrefix => "Input Prefix",
+            Str::SettingSuffix => "Input Suffix",
+            Str::SettingLanguage => "Language",
+            Str::SettingStartScreen => "Start Screen",
+            Str::StartMenu => "Menu",
+            Str::SettingTextSize => "Text Size",
+            Str::TextSmall => "Small",
+            Str::TextNormal => "Normal",
+            Str::TextLarge => "Large",
+            Str::SettingsHint => "Up/Down: select | Left/Right: change\nI: format info  Q: back",
+            Str::On => "On",
+            Str::Off => "Off",
+            Str::EnterTextTitle => "Enter Text",
+            Str::InputHint => "Type text, press Enter to generate barcode\nUp: add check digit  Down: format info",
+            Str::InfoChars => "Characters",
+            Str::InfoLength => "Length",
+            Str::InfoCheck => "Check digit",
+            Str::InfoExample => "Example",
+            Str::InfoHint => "Q: back",
+            Str::Empty => "(empty)",
+            Str::Format => "Format",
+            Str::Auto => "Auto",
+            Str::Or => "or",
+            Str::Wide => "wide",
+            Str::Tall => "tall",
+            Str::TrimmedFrom => "trimmed from",
+            Str::Valid => "OK",
+            Str::Invalid => "INVALID",
+            Str::InputNotValid => "Input not valid for this format",
+            Str::CheckDigit => "check",
+            Str::CheckDigitOk => "Check digit matches ✓",
+            Str::DigitsOnly => "Digits only",
+            Str::CheckDigitAdded => "Check digit added",
+            Str::CheckDigitNeedsDigits => "Needs 12 digits (EAN-13) or 11 (UPC-A)",
+            Str::MaxLength => "Max length reached",
+            Str::NeedsExtended39 => "needs Extended Code 39",
+            Str::DisplayHint => "S:save N:new Q:back",
+            Str::ScanOk => "scan OK",
+            Str::ScanThin => "thin: widen",
+            Str::ScanClipped => "clipped",
+            Str::SelfTest => "Self test",
+            Str::SelfTestHint => "L/R:next Q:back",
+            Str::SavedBrowseHint => "L/R:next Q:back",
+            Str::TooWide => "Too wide for a 1D code — shorten it",
+            Str::Tiles => "Copies",
+            Str::TileHint => "T:single Q:back",
+            Str::Verified => "Verified ✓",
+            Str::Mismatch => "Mismatch!",
+            Str::VerifyUnavailable => "verify unavailable",
+            Str::NotUpcA => "Only an EAN-13 starting with 0 is a UPC-A",
+            Str::SavePrompt => "Save this barcode?\n\nY: Yes  N: No",
+            Str::AlreadySavedAs => "Already saved as",
+            Str::SaveAnyway => "Save anyway? Y: Yes  N: No",
+            Str::DiscardPrompt => "Discard text?\n\nY: Yes  N: No",
+            Str::KeypadTitle => "Keypad",
+            Str::KeypadDelete => "Del",
+            Str::KeypadDone => "OK",
+            Str::KeypadHint => "Arrows: move  Enter: press  Q: back",
+            Str::ScanTest => "Scan test",
+            Str::ScanTestHint => "Any key: stop",
+            Str::SaveTitle => "Save Barcode",
+            Str::EnterName => "Enter a name:",
+            Str::TypeName => "(type a name)",
+            Str::SaveNameHint => "Enter: save | Q: cancel",
+            Str::SavedAs => "Saved as",
+            Str::NameBlank => "Name can't be blank",
+            Str::NothingToImport => "Nothing to import",
+            Str::Imported => "Imported",
+            Str::Exported => "Exported to barcode.codes:export:",
+            Str::SkippedLines => "skipped lines",
+            Str::AutoSavedAs => "Auto-saved as",
+            Str::PurgedAutoSaved => "Auto-saved codes deleted:",
+            Str::SaveFailedWrite => "Save failed — PDDB write error",
+            Str::SaveFailedUnmounted => "Save failed — PDDB not mounted",
+            Str::SaveFailedSerialize => "Save failed — could not encode the record",
+            Str::SavedTitle => "Saved Barcodes",
+            Str::NoSavedCodes => "No saved barcodes.\n\nPress Q to go back.",
+            Str::SavedListHint => "Enter:load C:copy G:group D:del U:undo",
+            Str::NothingToUndo => "Nothing to undo",
+            Str::CategoryTitle => "Category",
+            Str::EnterCategory => "Category for this code:",
+            Str::NoCategory => "(none)",
+            Str::CategoryHint => "Enter: set (empty clears) | Q: cancel",
+            Str::PrefixTitle => "Input Prefix",
+            Str::SuffixTitle => "Input Suffix",
+            Str::EnterAffix => "Added to every barcode:",
+            Str::AffixNone => "(none)",
+            Str::ComposeTitle => "Compose GTIN",
+            Str::EnterPrefix => "GS1 company prefix:",
+            Str::EnterItemRef => "Item reference:",
+            Str::TypeDigits => "(digits)",
+            Str::ComposeHint => "Enter: next | Q: cancel",
+            Str::ComposeLength => "Prefix + item must be 11 (UPC-A) or 12 (EAN-13) digits",
+            Str::PriceTitle => "Variable Weight UPC",
+            Str::EnterItemNumber => "Item number (5 digits):",
+            Str::EnterPrice => "Price in cents (up to 9999):",
+            Str::HelpTitle => "Help",
+        }
+    }
+
+    fn de(self) -> &'static str {
+        match self {
+            Str::AppTitle => "Barcode-Generator",
+            Str::SavedBarcodesCount => "gespeicherte Barcodes",
+            Str::LastCode => "Zuletzt",
+            Str::ShowAgainHint => "R: erneut zeigen",
+            Str::MenuNewBarcode => "Neuer Barcode",
+            Str::MenuComposeGtin => "GTIN zusammensetzen",
+            Str::MenuPriceCode => "Preiscode (UPC 2)",
+            Str::MenuSavedCodes => "Gespeicherte Codes",
+            Str::MenuSelfTest => "Selbsttest",
+            Str::MenuSettings => "Einstellungen",
+            Str::MenuHelp => "Hilfe",
+            Str::SettingFormat => "Format",
+            Str::SettingAutoDetect => "Auto-Erkennung",
+            Str::SettingBarWidth => "Balkenbreite",
+            Str::SettingBarHeight => "Balkenhöhe",
+            Str::SettingAutoHeight => "Auto-Höhe",
+            Str::SettingTrimInput => "Eingabe kürzen",
+            Str::SettingStripNonDigits => "Nur Ziffern",
+            Str::SettingMaxBrightness => "Max. Helligkeit",
+            Str::SettingScanTestTime => "Scantest-Dauer",
+            Str::SettingCode39Case => "C39 Kleinbuchst.",
+            Str::SettingStd2of5Check => "2 aus 5 Prüfz.",
+            Str::SettingShowCheckChar => "Prüfz. zeigen",
+            Str::SettingEanZeroPad => "EAN mit Nullen",
+            Str::SettingUpcText => "EAN 0 als UPC-Text",
+            Str::SettingWideRatio => "Breitverhältnis",
+            Str::SettingCode39Gap => "Code-39-Lücke",
+            Str::SettingCheckMode => "Prüfziffer",
+            Str::CheckRecompute => "Neu rechnen",
+            Str::CheckRequire => "Muss stimmen",
+            Str::CheckAppend => "Übernehmen",
+            Str::SettingAppendEnter => "C128 + Enter",
+            Str::SettingCode128ForceB => "C128 nur B",
+            Str::SettingCode128Latin1 => "C128 Latin-1",
+            Str::SettingResumeLast => "Letzten zeigen",
+            Str::SettingAutoSave => "Auto-Speichern",
+            Str::SettingPrivateMode => "Privatmodus",
+            Str::PrivateTag => "PRIVAT",
+            Str::SettingMirror => "Spiegeln",
+            Str::SettingCenterBars => "Balken zentrieren",
+            Str::SettingDrawFrame => "Rahmen zeichnen",
+            Str::SettingTileCount => "Druckkopien",
+            Str::SettingKeyRepeat => "Schnelle Wdh.",
+            Str::SettingPrefix => "Präfix",
+            Str::SettingSuffix => "Suffix",
+            Str::SettingLanguage => "Sprache",
+            Str::SettingStartScreen => "Startseite",
+            Str::StartMenu => "Menü",
+            Str::SettingTextSize => "Textgröße",
+            Str::TextSmall => "Klein",
+            Str::TextNormal => "Normal",
+            Str::TextLarge => "Groß",
+            Str::SettingsHint => "Auf/Ab: wählen | Links/Rechts: ändern\nI: Formatinfo  Q: zurück",
+            Str::On => "Ein",
+            Str::Off => "Aus",
+            Str::EnterTextTitle => "Text eingeben",
+            Str::InputHint => "Text eingeben, Enter erzeugt den Barcode\nAuf: Prüfziffer anhängen  Ab: Formatinfo",
+            Str::InfoChars => "Zeichen",
+            Str::InfoLength => "Länge",
+            Str::InfoCheck => "Prüfziffer",
+            Str::InfoExample => "Beispiel",
+            Str::InfoHint => "Q: zurück",
+            Str::Empty => "(leer)",
+            Str::Format => "Format",
+            Str::Auto => "Auto",
+            Str::Or => "oder",
+            Str::Wide => "breit",
+            Str::Tall => "hoch",
+            Str::TrimmedFrom => "gekürzt von",
+            Str::Valid => "OK",
+            Str::Invalid => "UNGÜLTIG",
+            Str::InputNotValid => "Eingabe passt nicht zum Format",
+            Str::CheckDigit => "Prüfziffer",
+            Str::CheckDigitOk => "Prüfziffer stimmt ✓",
+            Str::DigitsOnly => "Nur Ziffern",
+            Str::CheckDigitAdded => "Prüfziffer angehängt",
+            Str::CheckDigitNeedsDigits => "Braucht 12 Ziffern (EAN-13) oder 11 (UPC-A)",
+            Str::MaxLength => "Maximale Länge erreicht",
+            Str::NeedsExtended39 => "braucht Extended Code 39",
+            Str::DisplayHint => "S:speichern N:neu Q:zurück",
+            Str::ScanOk => "lesbar",
+            Str::ScanThin => "zu dünn",
+            Str::ScanClipped => "abgeschnitten",
+            Str::SelfTest => "Selbsttest",
+            Str::SelfTestHint => "L/R:weiter Q:zurück",
+            Str::SavedBrowseHint => "L/R:weiter Q:zurück",
+            Str::TooWide => "Zu breit für 1D-Code — kürzen",
+            Str::Tiles => "Kopien",
+            Str::TileHint => "T:einzeln Q:zurück",
+            Str::Verified => "Geprüft ✓",
+            Str::Mismatch => "Abweichung!",
+            Str::VerifyUnavailable => "Prüfung nicht verfügbar",
+            Str::NotUpcA => "Nur EAN-13 mit führender 0 ist UPC-A",
+            Str::SavePrompt => "Barcode speichern?\n\nY: Ja  N: Nein",
+            Str::AlreadySavedAs => "Schon gespeichert als",
+            Str::SaveAnyway => "Trotzdem? Y: Ja  N: Nein",
+            Str::DiscardPrompt => "Text verwerfen?\n\nY: Ja  N: Nein",
+            Str::KeypadTitle => "Ziffernblock",
+            Str::KeypadDelete => "Lösch",
+            Str::KeypadDone => "OK",
+            Str::KeypadHint => "Pfeile: wählen  Enter: drücken  Q: zurück",
+            Str::ScanTest => "Scantest",
+            Str::ScanTestHint => "Taste: Ende",
+            Str::SaveTitle => "Barcode speichern",
+            Str::EnterName => "Namen eingeben:",
+            Str::TypeName => "(Name)",
+            Str::SaveNameHint => "Enter: speichern | Q: abbrechen",
+            Str::SavedAs => "Gespeichert als",
+            Str::NameBlank => "Name darf nicht leer sein",
+            Str::NothingToImport => "Nichts zu importieren",
+            Str::Imported => "Importiert",
+            Str::Exported => "Nach barcode.codes:export exportiert:",
+            Str::SkippedLines => "übersprungen: Zeilen",
+            Str::AutoSavedAs => "Automatisch gespeichert als",
+            Str::PurgedAutoSaved => "Automatisch gespeicherte gelöscht:",
+            Str::SaveFailedWrite => "Speichern fehlgeschlagen — PDDB-Schreibfehler",
+            Str::SaveFailedUnmounted => "Speichern fehlgeschlagen — PDDB nicht eingehängt",
+            Str::SaveFailedSerialize => "Speichern fehlgeschlagen — Eintrag nicht kodierbar",
+            Str::SavedTitle => "Gespeicherte Barcodes",
+            Str::NoSavedCodes => "Keine gespeicherten Barcodes.\n\nQ drücken, um zurückzugehen.",
+            Str::SavedListHint => "Enter:laden C:kopie G:gruppe D:lösch. U:zurück",
+            Str::NothingToUndo => "Nichts rückgängig zu machen",
+            Str::CategoryTitle => "Kategorie",
+            Str::EnterCategory => "Kategorie für diesen Code:",
+            Str::NoCategory => "(keine)",
+            Str::CategoryHint => "Enter: setzen (leer löscht) | Q: abbrechen",
+            Str::PrefixTitle => "Präfix",
+            Str::SuffixTitle => "Suffix",
+            Str::EnterAffix => "Kommt an jeden Barcode:",
+            Str::AffixNone => "(keins)",
+            Str::ComposeTitle => "GTIN zusammensetzen",
+            Str::EnterPrefix => "GS1-Basisnummer:",
+            Str::EnterItemRef => "Artikelnummer:",
+            Str::TypeDigits => "(Ziffern)",
+            Str::ComposeHint => "Enter: weiter | Q: abbrechen",
+            Str::ComposeLength => "Basis + Artikel: 11 (UPC-A) oder 12 (EAN-13) Ziffern",
+            Str::PriceTitle => "Gewichts-UPC",
+            Str::EnterItemNumber => "Artikelnummer (5 Ziffern):",
+            Str::EnterPrice => "Preis in Cent (bis 9999):",
+            Str::HelpTitle => "Hilfe",
+        }
+    }
+}
+
+/// Help screen lines as (heading, text).
+pub fn help_lines(lang: Language) -> &'static [(bool, &'static str)] {
+    match lang {
+        Language::En => &[
+            (true, "Barcode Generator v0.1"),
+            (false, ""),
+            (true, "FORMATS"),
+            (false, "  Code 128: Full ASCII"),
+            (false, "  Code 39: A-Z, 0-9, symbols"),
+            (false, "  EAN-13: 12-13 digit products"),
+            (false, "  UPC-A: 11-12 digit products"),
+            (false, "  Telepen: ASCII  Std 2 of 5: digits"),
+            (false, "  Matrix 2 of 5: digits"),
+            (false, "  Code 32: 8-9 digit AIC  PZN: 7"),
+            (false, ""),
+            (true, "INPUT"),
+            (false, "  Type text, Enter to generate"),
+            (false, "  Left: undo last Backspace"),
+            (false, "  Right: take the auto-detect"),
+            (false, "  alternative in brackets"),
+            (false, "  Check Digit: a typed 13th (UPC:"),
+            (false, "  12th) digit is recomputed, must"),
+            (false, "  match, or is kept as typed"),
+            (false, "  Up: append EAN/UPC check digit"),
+            (false, "  Center: preview, stay in Input"),
+            (false, "  K: on-screen keypad (digit"),
+            (false, "  formats, Auto off)"),
+            (false, "  F1: Code 128  F2: Code 39"),
+            (false, "  F3: EAN-13    F4: UPC-A"),
+            (false, ""),
+            (true, "DISPLAY"),
+            (false, "  S: Save  N: New  Q: Back"),
+            (false, "  V: Verify  T: Print layout"),
+            (false, "  M: Mirror  U: EAN-13 as UPC-A"),
+            (false, "  P: Scan test, full brightness"),
+            (false, "  and height for a while"),
+            (false, "  a||b in the input shows two"),
+            (false, "  codes stacked"),
+            (false, "  Up/Down: Bar height"),
+            (false, "  Left/Right: Bar width, or the"),
+            (false, "  next saved code if opened from"),
+            (false, "  Saved Codes"),
+            (false, ""),
+            (true, "SAVED CODES"),
+            (false, "  Enter: Load  C: Copy  D: Delete"),
+            (false, "  G: Category  Left/Right: Filter"),
+            (false, "  U: Undo delete"),
+            (false, "  P: Delete all auto-saved codes"),
+            (false, "  I: Import name,GTIN lines"),
+            (false, "     from barcode.codes:import"),
+            (false, "  E: Export to barcode.codes:export,"),
+            (false, "     tab-separated; I reads it back"),
+            (false, ""),
+            (true, "Auto-detect picks format"),
+            (false, "from your input text."),
+        ],
+        Language::De => &[
+            (true, "Barcode-Generator v0.1"),
+            (false, ""),
+            (true, "FORMATE"),
+            (false, "  Code 128: voller ASCII-Satz"),
+            (false, "  Code 39: A-Z, 0-9, Symbole"),
+            (false, "  EAN-13: 12-13 Ziffern"),
+            (false, "  UPC-A: 11-12 Ziffern"),
+            (false, "  Telepen: ASCII  Std 2 of 5: Ziffern"),
+            (false, "  Matrix 2 of 5: Ziffern"),
+            (false, "  Code 32: 8-9 Ziffern  PZN: 7"),
+            (false, ""),
+            (true, "EINGABE"),
+            (false, "  Text tippen, Enter erzeugt"),
+            (false, "  Links: Rücktaste rückgängig"),
+            (false, "  Rechts: Alternative der Auto-"),
+            (false, "  Erkennung (in Klammern)"),
+            (false, "  Prüfziffer: eine getippte 13."),
+            (false, "  (UPC: 12.) Ziffer wird neu"),
+            (false, "  berechnet, muss stimmen oder"),
+            (false, "  bleibt wie getippt"),
+            (false, "  Auf: EAN/UPC-Prüfziffer anhängen"),
+            (false, "  Mitte: Vorschau, bleibt in Eingabe"),
+            (false, "  K: Ziffernblock (Ziffernformate,"),
+            (false, "  Auto aus)"),
+            (false, "  F1: Code 128  F2: Code 39"),
+            (false, "  F3: EAN-13    F4: UPC-A"),
+            (false, ""),
+            (true, "ANZEIGE"),
+            (false, "  S: Speichern  N: Neu  Q: Zurück"),
+            (false, "  V: Prüfen  T: Drucklayout"),
+            (false, "  M: Spiegeln  U: EAN-13 als UPC-A"),
+            (false, "  P: Scantest, volle Helligkeit"),
+            (false, "  und Höhe für eine Weile"),
+            (false, "  a||b in der Eingabe zeigt"),
+            (false, "  zwei Codes übereinander"),
+            (false, "  Auf/Ab: Balkenhöhe"),
+            (false, "  Links/Rechts: Balkenbreite, bei"),
+            (false, "  gespeicherten Codes der nächste"),
+            (false, ""),
+            (true, "GESPEICHERTE CODES"),
+            (false, "  Enter: Laden  C: Kopie  D: Löschen"),
+            (false, "  G: Kategorie  Links/Rechts: Filter"),
+            (false, "  U: Löschen rückgängig"),
+            (false, "  P: Auto-gespeicherte löschen"),
+            (false, "  I: Name,GTIN-Zeilen aus"),
+            (false, "     barcode.codes:import laden"),
+            (false, "  E: Nach barcode.codes:export,"),
+            (false, "     tabgetrennt; I liest es ein"),
+            (false, ""),
+            (true, "Auto-Erkennung wählt das"),
+            (false, "Format anhand der Eingabe."),
+        ],
+    }
+}
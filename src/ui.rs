@@ -1,25 +1,114 @@
 //! UI rendering for the Barcode Generator.
 
-use crate::app::{AppState, BarcodeApp, MenuItem};
-use crate::barcode_encode;
+use crate::app::{
+    AppState, BarcodeApp, CategoryFilter, KeypadKey, MenuItem, RedrawRegion, RenderCache, SettingItem,
+    TextSize, Thumbnail, KEYPAD_COLUMNS, KEYPAD_KEYS, SELF_TEST_SAMPLES, STACK_DELIMITER, THUMB_WIDTH,
+};
+use crate::barcode_encode::{self, Barcode, BarcodeFormat, CheckMode};
+use crate::strings::{self, Str};
 
 use gam::*;
 
-const SCREEN_WIDTH: isize = 336;
 const HEADER_HEIGHT: isize = 30;
 const FOOTER_HEIGHT: isize = 46;
 const CONTENT_TOP: isize = HEADER_HEIGHT;
-const CONTENT_BOTTOM: isize = 536 - FOOTER_HEIGHT;
-const CONTENT_HEIGHT: isize = CONTENT_BOTTOM - CONTENT_TOP;
+
+/// Content canvas size, from the bounds `draw` is given; everything below
+/// the header is laid out from it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Layout {
+    width: isize,
+    height: isize,
+}
+
+impl Layout {
+    fn content_bottom(&self) -> isize {
+        self.height - FOOTER_HEIGHT
+    }
+
+    fn content_height(&self) -> isize {
+        self.content_bottom() - CONTENT_TOP
+    }
+
+    fn input_bottom(&self) -> isize {
+        self.content_bottom() - 100
+    }
+
+    /// Settings rows that fit above the key hint.
+    fn settings_rows(&self) -> usize {
+        let room = self.content_height() - SETTINGS_TOP - SETTINGS_HINT_HEIGHT;
+        (room / SETTINGS_ROW_HEIGHT).max(1) as usize
+    }
+}
 
 const REGULAR_HEIGHT: isize = 15;
 const LINE_GAP: isize = 4;
 const LINE_HEIGHT: isize = REGULAR_HEIGHT + LINE_GAP;
 
-pub fn draw(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
-    // Clear screen
+const TILE_BAR_HEIGHT: isize = 60;
+const TILE_GAP: isize = 8;
+
+/// Advance of one `GlyphStyle::Monospace` character, in pixels.
+const MONO_CHAR_WIDTH: isize = 7;
+
+const INPUT_TOP: isize = CONTENT_TOP + 40;
+
+/// Draw the current screen on `canvas`, laid out for its bounds `screen`.
+pub fn draw(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid, screen: Point) {
+    let layout = Layout { width: screen.x, height: screen.y };
+    app.canvas_width.set(layout.width);
+    // Incremental updates repaint only the region that changed
+    match app.redraw_region {
+        RedrawRegion::InputText if app.state == AppState::Input => {
+            clear_region(gam, canvas, 9, INPUT_TOP + 1, layout.width - 9, layout.input_bottom() - 1);
+            draw_input_text(layout, app, gam, canvas);
+            clear_region(gam, canvas, 0, layout.input_bottom() + 1, layout.width, layout.content_bottom() - 1);
+            draw_input_status(layout, app, gam, canvas);
+        }
+        RedrawRegion::MenuRows(a, b) if app.state == AppState::MainMenu => {
+            for i in [a, b] {
+                let y = menu_row_y(i);
+                clear_region(gam, canvas, 0, y - 2, layout.width, y + LINE_HEIGHT + 3);
+                draw_menu_row(layout, app, gam, canvas, i);
+            }
+        }
+        // Moving the highlight past the visible rows scrolls, which needs a full redraw
+        RedrawRegion::SettingsRows(a, b) if app.state == AppState::Settings && settings_scroll(layout, a) == settings_scroll(layout, b) => {
+            for i in [a, b] {
+                let y = settings_row_y(layout, app, i);
+                clear_region(gam, canvas, 0, y - 4, layout.width, y + LINE_HEIGHT + 5);
+                draw_settings_row(layout, app, gam, canvas, i);
+            }
+        }
+        _ => {
+            clear_region(gam, canvas, 0, 0, layout.width, layout.height);
+
+            match app.state {
+                AppState::MainMenu => draw_main_menu(layout, app, gam, canvas),
+                AppState::Input => draw_input(layout, app, gam, canvas),
+                AppState::Display => draw_display(layout, app, gam, canvas),
+                AppState::SavePrompt => draw_save_prompt(layout, app, gam, canvas),
+                AppState::SaveNameEntry => draw_save_name(layout, app, gam, canvas),
+                AppState::ConfirmDuplicate => draw_confirm_duplicate(layout, app, gam, canvas),
+                AppState::ConfirmDiscard => draw_confirm_discard(layout, app, gam, canvas),
+                AppState::LoadList => draw_load_list(layout, app, gam, canvas),
+                AppState::CategoryEntry => draw_category_entry(layout, app, gam, canvas),
+                AppState::AffixEntry => draw_affix_entry(layout, app, gam, canvas),
+                AppState::Settings => draw_settings(layout, app, gam, canvas),
+                AppState::Help => draw_help(layout, app, gam, canvas),
+                AppState::FormatInfo => draw_format_info(layout, app, gam, canvas),
+                AppState::ComposeGtin => draw_compose(layout, app, gam, canvas),
+                AppState::Keypad => draw_keypad(layout, app, gam, canvas),
+            }
+        }
+    }
+
+    gam.redraw().ok();
+}
+
+fn clear_region(gam: &Gam, canvas: graphics_server::Gid, x0: isize, y0: isize, x1: isize, y1: isize) {
     let clear = graphics_server::Rectangle::new_coords_with_style(
-        0, 0, SCREEN_WIDTH, 536,
+        x0, y0, x1, y1,
         graphics_server::DrawStyle::new(
             graphics_server::PixelColor::Light,
             graphics_server::PixelColor::Light,
@@ -27,24 +116,11 @@ pub fn draw(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
         ),
     );
     gam.draw_rectangle(canvas, clear).ok();
-
-    match app.state {
-        AppState::MainMenu => draw_main_menu(app, gam, canvas),
-        AppState::Input => draw_input(app, gam, canvas),
-        AppState::Display => draw_display(app, gam, canvas),
-        AppState::SavePrompt => draw_save_prompt(app, gam, canvas),
-        AppState::SaveNameEntry => draw_save_name(app, gam, canvas),
-        AppState::LoadList => draw_load_list(app, gam, canvas),
-        AppState::Settings => draw_settings(app, gam, canvas),
-        AppState::Help => draw_help(app, gam, canvas),
-    }
-
-    gam.redraw().ok();
 }
 
-fn draw_header(gam: &Gam, canvas: graphics_server::Gid, title: &str) {
+fn draw_header(layout: Layout, gam: &Gam, canvas: graphics_server::Gid, title: &str) {
     let bg = graphics_server::Rectangle::new_coords_with_style(
-        0, 0, SCREEN_WIDTH, HEADER_HEIGHT,
+        0, 0, layout.width, HEADER_HEIGHT,
         graphics_server::DrawStyle::new(
             graphics_server::PixelColor::Dark,
             graphics_server::PixelColor::Dark,
@@ -55,7 +131,7 @@ fn draw_header(gam: &Gam, canvas: graphics_server::Gid, title: &str) {
 
     let mut tv = TextView::new(
         canvas,
-        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(8, 2, SCREEN_WIDTH - 8, HEADER_HEIGHT - 2)),
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(8, 2, layout.width - 8, HEADER_HEIGHT - 2)),
     );
     tv.style = GlyphStyle::Bold;
     tv.invert = true;
@@ -65,12 +141,12 @@ fn draw_header(gam: &Gam, canvas: graphics_server::Gid, title: &str) {
     gam.post_textview(&mut tv).ok();
 }
 
-fn draw_footer(gam: &Gam, canvas: graphics_server::Gid, labels: &[&str; 4]) {
-    let y = CONTENT_BOTTOM;
-    let zone_width = SCREEN_WIDTH / 4;
+fn draw_footer(layout: Layout, gam: &Gam, canvas: graphics_server::Gid, labels: &[&str; 4]) {
+    let y = layout.content_bottom();
+    let zone_width = layout.width / 4;
 
     let sep = graphics_server::Line::new_with_style(
-        Point::new(0, y), Point::new(SCREEN_WIDTH, y),
+        Point::new(0, y), Point::new(layout.width, y),
         graphics_server::DrawStyle::new(
             graphics_server::PixelColor::Dark,
             graphics_server::PixelColor::Dark,
@@ -96,78 +172,108 @@ fn draw_footer(gam: &Gam, canvas: graphics_server::Gid, labels: &[&str; 4]) {
     }
 }
 
-fn draw_main_menu(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
-    draw_header(gam, canvas, "Barcode Generator");
-
-    let items = MenuItem::all();
-    for (i, item) in items.iter().enumerate() {
-        let y = CONTENT_TOP + 20 + (i as isize) * (LINE_HEIGHT + 8);
-        let selected = i == app.menu_index;
+fn draw_main_menu(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_header(layout, gam, canvas, app.tr(Str::AppTitle));
 
-        if selected {
-            let hl = graphics_server::Rectangle::new_coords_with_style(
-                8, y - 2, SCREEN_WIDTH - 8, y + LINE_HEIGHT + 2,
-                graphics_server::DrawStyle::new(
-                    graphics_server::PixelColor::Dark,
-                    graphics_server::PixelColor::Dark,
-                    0,
-                ),
-            );
-            gam.draw_rectangle(canvas, hl).ok();
-        }
+    for i in 0..MenuItem::all().len() {
+        draw_menu_row(layout, app, gam, canvas, i);
+    }
 
+    if app.saved_count() > 0 {
+        let y = menu_row_y(MenuItem::all().len()) + 20;
         let mut tv = TextView::new(
             canvas,
-            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-                16, y, SCREEN_WIDTH - 16, y + LINE_HEIGHT,
-            )),
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, layout.width - 16, y + LINE_HEIGHT)),
         );
-        tv.style = GlyphStyle::Regular;
-        tv.invert = selected;
+        tv.style = GlyphStyle::Small;
         tv.draw_border = false;
         tv.margin = Point::new(0, 0);
-        write!(tv, "{}", item.label()).ok();
+        write!(tv, "{} {}", app.saved_count(), app.tr(Str::SavedBarcodesCount)).ok();
         gam.post_textview(&mut tv).ok();
     }
 
-    if !app.saved_codes.is_empty() {
-        let y = CONTENT_TOP + 20 + (LINE_HEIGHT + 8) * 4 + 20;
+    // The code last shown, which R brings back, unless it's private
+    if let Some(barcode) = app.barcode.as_ref().filter(|_| !app.settings.private_mode) {
+        let y = menu_row_y(MenuItem::all().len()) + 20 + LINE_HEIGHT + 4;
         let mut tv = TextView::new(
             canvas,
-            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, SCREEN_WIDTH - 16, y + LINE_HEIGHT)),
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, layout.width - 16, y + LINE_HEIGHT)),
         );
         tv.style = GlyphStyle::Small;
         tv.draw_border = false;
         tv.margin = Point::new(0, 0);
-        write!(tv, "{} saved barcodes", app.saved_codes.len()).ok();
+        let text: String = barcode.text.chars().filter(|c| !c.is_control()).collect();
+        write!(
+            tv,
+            "{}: {} {}  {}",
+            app.tr(Str::LastCode),
+            text,
+            barcode.format.short(),
+            app.tr(Str::ShowAgainHint),
+        ).ok();
         gam.post_textview(&mut tv).ok();
     }
 
-    draw_footer(gam, canvas, &["", "", "", ""]);
+    draw_footer(layout, gam, canvas, &["", "", "", ""]);
+}
+
+fn menu_row_y(i: usize) -> isize {
+    CONTENT_TOP + 20 + (i as isize) * (LINE_HEIGHT + 8)
+}
+
+fn draw_menu_row(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid, i: usize) {
+    let item = match MenuItem::all().get(i) {
+        Some(item) => item,
+        None => return,
+    };
+    let y = menu_row_y(i);
+    let selected = i == app.menu_index;
+
+    if selected {
+        let hl = graphics_server::Rectangle::new_coords_with_style(
+            8, y - 2, layout.width - 8, y + LINE_HEIGHT + 2,
+            graphics_server::DrawStyle::new(
+                graphics_server::PixelColor::Dark,
+                graphics_server::PixelColor::Dark,
+                0,
+            ),
+        );
+        gam.draw_rectangle(canvas, hl).ok();
+    }
+
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+            16, y, layout.width - 16, y + LINE_HEIGHT,
+        )),
+    );
+    tv.style = GlyphStyle::Regular;
+    tv.invert = selected;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    write!(tv, "{}", app.tr(item.label())).ok();
+    gam.post_textview(&mut tv).ok();
 }
 
-fn draw_input(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
-    draw_header(gam, canvas, "Enter Text");
+fn draw_input(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_header(layout, gam, canvas, app.tr(Str::EnterTextTitle));
 
     // Instructions
     let mut tv = TextView::new(
         canvas,
         TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-            8, CONTENT_TOP + 8, SCREEN_WIDTH - 8, CONTENT_TOP + 8 + LINE_HEIGHT * 2,
+            8, CONTENT_TOP + 8, layout.width - 8, CONTENT_TOP + 8 + LINE_HEIGHT * 2,
         )),
     );
     tv.style = GlyphStyle::Small;
     tv.draw_border = false;
     tv.margin = Point::new(0, 0);
-    write!(tv, "Type text, press Enter to generate barcode").ok();
+    write!(tv, "{}", app.tr(Str::InputHint)).ok();
     gam.post_textview(&mut tv).ok();
 
     // Input box
-    let input_top = CONTENT_TOP + 40;
-    let input_bottom = CONTENT_BOTTOM - 100;
-
     let border = graphics_server::Rectangle::new_coords_with_style(
-        8, input_top, SCREEN_WIDTH - 8, input_bottom,
+        8, INPUT_TOP, layout.width - 8, layout.input_bottom(),
         graphics_server::DrawStyle {
             fill_color: Some(graphics_server::PixelColor::Light),
             stroke_color: Some(graphics_server::PixelColor::Dark),
@@ -176,11 +282,18 @@ fn draw_input(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
     );
     gam.draw_rectangle(canvas, border).ok();
 
-    let display_text = if app.input_text.is_empty() { "(empty)" } else { &app.input_text };
+    draw_input_text(layout, app, gam, canvas);
+    draw_input_status(layout, app, gam, canvas);
+
+    draw_footer(layout, gam, canvas, &["C128", "C39", "EAN13", "UPC-A"]);
+}
+
+fn draw_input_text(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    let display_text = if app.input_text.is_empty() { app.tr(Str::Empty) } else { &app.input_text };
     let mut tv = TextView::new(
         canvas,
         TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-            16, input_top + 8, SCREEN_WIDTH - 16, input_bottom - 8,
+            16, INPUT_TOP + 8, layout.width - 16, layout.input_bottom() - 8,
         )),
     );
     tv.style = GlyphStyle::Monospace;
@@ -189,19 +302,102 @@ fn draw_input(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
     write!(tv, "{}", display_text).ok();
     gam.post_textview(&mut tv).ok();
 
-    // Status line
-    let y_status = input_bottom + 8;
-    let format = app.active_format();
-    let valid = if app.input_text.is_empty() {
-        true
-    } else {
-        barcode_encode::is_valid(&app.input_text, format)
+    // Invert the first character the format rejects. Long input wraps at the
+    // box edge, one monospace cell per character.
+    if let Some(index) = app.invalid_input_index() {
+        let per_line = ((layout.width - 32) / MONO_CHAR_WIDTH) as usize;
+        let x = 16 + (index % per_line) as isize * MONO_CHAR_WIDTH;
+        let y = INPUT_TOP + 8 + (index / per_line) as isize * REGULAR_HEIGHT;
+        if y + REGULAR_HEIGHT <= layout.input_bottom() - 8 {
+            let c = app.input_text.chars().nth(index).unwrap_or(' ');
+            let mut tv = TextView::new(
+                canvas,
+                TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(x, y, x + MONO_CHAR_WIDTH, y + REGULAR_HEIGHT)),
+            );
+            tv.style = GlyphStyle::Monospace;
+            tv.invert = true;
+            tv.draw_border = false;
+            tv.margin = Point::new(0, 0);
+            write!(tv, "{}", c).ok();
+            gam.post_textview(&mut tv).ok();
+        }
+    }
+
+    // Eleven UPC-A digits typed: show the check digit that would follow,
+    // inverted in the next cell, to compare with a printed code
+    if app.active_format() == BarcodeFormat::UpcA && app.input_text.len() == 11 {
+        if let Some(check) = gtin_check(&app.input_text, BarcodeFormat::UpcA) {
+            let x = 16 + 11 * MONO_CHAR_WIDTH;
+            let y = INPUT_TOP + 8;
+            let mut tv = TextView::new(
+                canvas,
+                TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(x, y, x + MONO_CHAR_WIDTH, y + REGULAR_HEIGHT)),
+            );
+            tv.style = GlyphStyle::Monospace;
+            tv.invert = true;
+            tv.draw_border = false;
+            tv.margin = Point::new(0, 0);
+            write!(tv, "{}", check).ok();
+            gam.post_textview(&mut tv).ok();
+        }
+    }
+
+    if let Some(barcode) = app.input_preview() {
+        draw_inline_preview(layout, gam, canvas, barcode);
+    }
+}
+
+/// Height of the Center key preview strip at the bottom of the input box.
+const INLINE_PREVIEW_HEIGHT: isize = 32;
+
+/// Draw the symbol, without its quiet zones, across the bottom of the input
+/// box: whole pixels per module when it fits, else one sampled module per
+/// pixel column. A look at the shape, not something to scan.
+fn draw_inline_preview(layout: Layout, gam: &Gam, canvas: graphics_server::Gid, barcode: &Barcode) {
+    let (first, last) = match dark_span(barcode) {
+        Some(span) => span,
+        None => return,
     };
+    let avail = (layout.width - 32) as usize;
+    let span = last + 1 - first;
+    let cols = span.min(avail);
+    let w = (avail / cols) as isize;
+    let x0 = 16 + (avail - cols * w as usize) as isize / 2;
+    let y1 = layout.input_bottom() - 8;
+    let y0 = y1 - INLINE_PREVIEW_HEIGHT;
+    let style = graphics_server::DrawStyle::new(
+        graphics_server::PixelColor::Dark,
+        graphics_server::PixelColor::Dark,
+        0,
+    );
+    let mut c = 0;
+    while c < cols {
+        if !barcode.modules.get(first + c * span / cols) {
+            c += 1;
+            continue;
+        }
+        let start = c;
+        while c < cols && barcode.modules.get(first + c * span / cols) {
+            c += 1;
+        }
+        let rect = graphics_server::Rectangle::new_coords_with_style(
+            x0 + start as isize * w, y0, x0 + c as isize * w, y1, style,
+        );
+        gam.draw_rectangle(canvas, rect).ok();
+    }
+}
+
+fn draw_input_status(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    let y_status = layout.input_bottom() + 8;
+    let format = app.active_format();
+    let text = app.effective_input();
+    let payload = app.payload();
+    let valid = text.is_empty() || app.input_is_valid();
 
     let mut tv = TextView::new(
         canvas,
         TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-            8, y_status, SCREEN_WIDTH - 8, y_status + LINE_HEIGHT * 3,
+            8, y_status, layout.width - 8, y_status + LINE_HEIGHT * 3,
         )),
     );
     tv.style = GlyphStyle::Small;
@@ -210,49 +406,180 @@ fn draw_input(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
     if app.input_text.is_empty() {
         write!(
             tv,
-            "Format: {} | Auto: {}\n{}px wide, {}px tall",
+            "{}: {} | {}: {}\n{}px {}, {}px {}",
+            app.tr(Str::Format),
             format.label(),
-            if app.settings.auto_format { "On" } else { "Off" },
+            app.tr(Str::Auto),
+            on_off(app, app.settings.auto_format),
             app.settings.bar_width,
+            app.tr(Str::Wide),
             app.settings.bar_height,
+            app.tr(Str::Tall),
         ).ok();
     } else {
+        let (chars, typed) = (text.chars().count(), app.input_text.chars().count());
+        let length = if chars != typed {
+            format!("{}ch ({} {})", chars, app.tr(Str::TrimmedFrom), typed)
+        } else {
+            format!("{}ch", chars)
+        };
+        let format_desc = if app.settings.auto_format {
+            let first = payload.split(STACK_DELIMITER).next().unwrap_or(&payload);
+            match barcode_encode::auto_detect_ranked(first).get(1) {
+                Some(alt) => format!("{}: {} ({} {})", app.tr(Str::Auto), format.label(), app.tr(Str::Or), alt.label()),
+                None => format!("{}: {}", app.tr(Str::Auto), format.label()),
+            }
+        } else {
+            String::from(format.label())
+        };
+        let detail = if !valid {
+            String::from(app.tr(Str::InputNotValid))
+        } else {
+            gtin_check(&payload, format)
+                .map(|check| format!("{}: {}", app.tr(Str::CheckDigit), check))
+                .unwrap_or_default()
+        };
         write!(
             tv,
-            "{}ch | {} | {}\n{}",
-            app.input_text.len(),
-            format.label(),
-            if valid { "OK" } else { "INVALID" },
-            if !valid { "Input not valid for this format" } else { "" },
+            "{} | {} | {}\n{}",
+            length,
+            format_desc,
+            app.tr(if valid { Str::Valid } else { Str::Invalid }),
+            detail,
         ).ok();
     }
     gam.post_textview(&mut tv).ok();
 
-    draw_footer(gam, canvas, &["C128", "C39", "EAN13", "UPC-A"]);
+    // Key feedback or the encode failure from the last Enter, inverted so it stands out
+    if let Some(err) = app.status_message.as_ref().or(app.last_error.as_ref()) {
+        let y_err = y_status + LINE_HEIGHT * 2 + 4;
+        let bg = graphics_server::Rectangle::new_coords_with_style(
+            8, y_err - 2, layout.width - 8, y_err + LINE_HEIGHT + 2,
+            graphics_server::DrawStyle::new(
+                graphics_server::PixelColor::Dark,
+                graphics_server::PixelColor::Dark,
+                0,
+            ),
+        );
+        gam.draw_rectangle(canvas, bg).ok();
+
+        let mut tv = TextView::new(
+            canvas,
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+                12, y_err, layout.width - 12, y_err + LINE_HEIGHT,
+            )),
+        );
+        tv.style = GlyphStyle::Small;
+        tv.invert = true;
+        tv.draw_border = false;
+        tv.margin = Point::new(0, 0);
+        write!(tv, "{}", err).ok();
+        gam.post_textview(&mut tv).ok();
+    }
+}
+
+/// Check digit for an EAN-13 or UPC-A payload, once all of its data digits
+/// have been typed, so it can be compared with a printed code.
+fn gtin_check(text: &str, format: BarcodeFormat) -> Option<u8> {
+    let data_len = match format {
+        BarcodeFormat::Ean13 => 12,
+        BarcodeFormat::UpcA => 11,
+        _ => return None,
+    };
+    if text.len() < data_len || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let digits: Vec<u8> = text.bytes().take(data_len).map(|b| b - b'0').collect();
+    Some(match format {
+        BarcodeFormat::Ean13 => barcode_encode::ean13_check_digit(&digits),
+        _ => barcode_encode::upc_check_digit(&digits),
+    })
+}
+
+/// Narrowest element, in pixels, a phone camera reliably resolves at a
+/// normal holding distance. At about 0.13mm per pixel, one pixel is below
+/// what most phone scanners manage.
+const MIN_SCAN_ELEMENT_PX: isize = 2;
+
+/// Rough guess at whether the symbol will scan. Every format here has a
+/// one-module narrowest element, so that is just the bar width.
+fn scan_hint(layout: Layout, bar_w: isize, total_w: isize) -> Str {
+    if total_w > layout.width - 8 {
+        Str::ScanClipped
+    } else if bar_w < MIN_SCAN_ELEMENT_PX {
+        Str::ScanThin
+    } else {
+        Str::ScanOk
+    }
+}
+
+/// Module count above which 1px bars get the grey preview strip. Long codes
+/// at 1px have the most single-pixel elements to lose.
+const GREY_PREVIEW_MODULES: usize = 120;
+
+/// Knock every other pixel row out of the top quarter of the bars, so that
+/// strip shows roughly how 1px bars come out on a panel or printer that
+/// can't hold them. The rest of the symbol stays solid for scanning.
+fn draw_grey_preview(gam: &Gam, canvas: graphics_server::Gid, x: isize, y: isize, w: isize, bar_h: isize) {
+    let style = graphics_server::DrawStyle::new(
+        graphics_server::PixelColor::Light,
+        graphics_server::PixelColor::Light,
+        0,
+    );
+    for row in (0..bar_h / 4).step_by(2) {
+        let line = graphics_server::Rectangle::new_coords_with_style(x, y + row, x + w, y + row + 1, style);
+        gam.draw_rectangle(canvas, line).ok();
+    }
 }
 
-fn draw_display(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+fn draw_display(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
     if let Some(ref barcode) = app.barcode {
+        if app.show_bits {
+            draw_module_bits(layout, gam, canvas, barcode);
+            return;
+        }
+        if app.tile_mode {
+            draw_tiles(layout, app, gam, canvas, barcode);
+            return;
+        }
+        if let Some(ref second) = app.stacked {
+            draw_stacked(layout, app, gam, canvas, barcode, second);
+            return;
+        }
         let bar_w = app.settings.bar_width as isize;
-        let bar_h = app.settings.bar_height as isize;
+        // Room under the bars for the text line, which grows with its size,
+        // and the status line. Tall bars are cut short to keep both on screen.
+        let (text_style, text_h) = text_glyph(app.settings.text_size);
+        let text_line = text_h + LINE_GAP;
+        let DisplayRows { bar_h, y_offset, text_y, status_y } =
+            display_rows(layout, app.settings.bar_height as isize, app.full_height(), text_line);
         let total_w = barcode.modules.len() as isize * bar_w;
 
-        // Center barcode
-        let x_offset = (SCREEN_WIDTH - total_w).max(0) / 2;
-        let y_offset = (CONTENT_HEIGHT - bar_h - 40).max(0) / 2 + CONTENT_TOP;
-
+        // Center barcode, or just its bars when quiet zones would pull it off
+        let bars = if app.settings.center_bars { dark_span(barcode) } else { None };
+        let (x_offset, fit_w) = match bars {
+            Some((first, last)) => {
+                let span_w = (last + 1 - first) as isize * bar_w;
+                let lead = if app.settings.mirror { barcode.modules.len() - 1 - last } else { first };
+                ((layout.width - span_w) / 2 - lead as isize * bar_w, span_w)
+            }
+            None => ((layout.width - total_w).max(0) / 2, total_w),
+        };
         // If barcode is too wide, just start from left edge with small margin
-        let x_start = if total_w > SCREEN_WIDTH - 8 { 4 } else { x_offset };
-
-        // Draw bars
-        for (i, &dark) in barcode.modules.iter().enumerate() {
-            if dark {
-                let x = x_start + (i as isize) * bar_w;
-                if x + bar_w > SCREEN_WIDTH {
-                    break; // clip to screen
-                }
+        let x_start = if fit_w > layout.width - 8 { 4 } else { x_offset };
+
+        // Draw bars from the cache. A 13-char Code 128 ("Hello, World!") is
+        // 198 modules of which 82 are dark, merged into 49 bars: redraws make
+        // 49 GAM calls instead of 82 and skip all the per-module geometry.
+        let mut cache = app.render_cache.borrow_mut();
+        let screen = (layout.width, layout.height);
+        if !cache.as_ref().is_some_and(|c| c.matches(barcode, &app.settings, app.full_height(), screen)) {
+            *cache = Some(layout_bars(layout, barcode, app, bar_h, x_start, y_offset));
+        }
+        if let Some(ref cache) = *cache {
+            for &(x0, y0, x1, y1) in cache.rects.iter() {
                 let rect = graphics_server::Rectangle::new_coords_with_style(
-                    x, y_offset, x + bar_w, y_offset + bar_h,
+                    x0, y0, x1, y1,
                     graphics_server::DrawStyle::new(
                         graphics_server::PixelColor::Dark,
                         graphics_server::PixelColor::Dark,
@@ -262,54 +589,420 @@ fn draw_display(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
                 gam.draw_rectangle(canvas, rect).ok();
             }
         }
+        if bar_w == 1 && barcode.modules.len() > GREY_PREVIEW_MODULES {
+            draw_grey_preview(gam, canvas, x_start, y_offset, total_w.min(layout.width - x_start), bar_h);
+        }
+        if app.show_ruler {
+            draw_ruler(layout, gam, canvas, barcode.modules.len(), bar_w, x_start, y_offset);
+        }
+        if app.settings.draw_frame {
+            draw_frame(layout, gam, canvas, x_start, y_offset, total_w, bar_h);
+        }
 
-        // Human-readable text below bars
-        let text_y = y_offset + bar_h + 8;
-        if text_y + LINE_HEIGHT < CONTENT_BOTTOM {
+        // Human-readable text below bars, as UPC-A when toggled with U (which
+        // also relabels it) or with the UPC Text setting
+        let upc = if app.upc_view { app.upc_form(barcode) } else { None };
+        let upc_text = if app.upc_view || app.settings.upc_text { app.upc_form(barcode) } else { None };
+        if text_y + text_line < layout.content_bottom() {
             let mut tv = TextView::new(
                 canvas,
                 TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-                    8, text_y, SCREEN_WIDTH - 8, text_y + LINE_HEIGHT,
+                    8, text_y, layout.width - 8, text_y + text_line,
                 )),
             );
-            tv.style = GlyphStyle::Monospace;
+            tv.style = text_style;
             tv.draw_border = false;
             tv.margin = Point::new(0, 0);
-            write!(tv, "{}", barcode.text).ok();
+            // Control characters (an appended CR) have no glyph
+            let printable: String = match upc_text {
+                Some(upc) => upc,
+                None => barcode.text.chars().filter(|c| !c.is_control()).collect(),
+            };
+            write!(tv, "{}", printable).ok();
             gam.post_textview(&mut tv).ok();
         }
 
         // Status line
-        let status_y = text_y + LINE_HEIGHT + 4;
-        if status_y + LINE_HEIGHT < 536 {
+        if status_y + LINE_HEIGHT <= layout.content_bottom() {
             let mut tv = TextView::new(
                 canvas,
                 TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-                    4, status_y, SCREEN_WIDTH - 4, status_y + LINE_HEIGHT,
+                    4, status_y, layout.width - 4, status_y + LINE_HEIGHT,
                 )),
             );
             tv.style = GlyphStyle::Small;
             tv.draw_border = false;
             tv.margin = Point::new(0, 0);
-            write!(
-                tv,
-                "{} {}w {}h  S:save N:new Q:back",
-                barcode.format.short(),
-                bar_w,
-                bar_h,
-            ).ok();
+            if let Some(ref msg) = app.status_message {
+                tv.style = GlyphStyle::Bold;
+                write!(tv, "{}", msg).ok();
+            } else if let Some(left) = app.scan_test {
+                tv.style = GlyphStyle::Bold;
+                write!(tv, "{} {}s  {}", app.tr(Str::ScanTest), left, app.tr(Str::ScanTestHint)).ok();
+            } else if app.from_saved {
+                let visible = app.visible_codes();
+                let name = visible.get(app.load_index).map_or("", |&i| app.saved_codes[i].name.as_str());
+                write!(
+                    tv,
+                    "{}/{} {} {}  {}",
+                    app.load_index + 1,
+                    visible.len(),
+                    name,
+                    barcode.format.short(),
+                    app.tr(Str::SavedBrowseHint),
+                ).ok();
+            } else if let Some(index) = app.self_test_index {
+                write!(
+                    tv,
+                    "{} {}/{} {}  {}",
+                    app.tr(Str::SelfTest),
+                    index + 1,
+                    SELF_TEST_SAMPLES.len(),
+                    barcode.format.short(),
+                    app.tr(Str::SelfTestHint),
+                ).ok();
+            } else {
+                let height = if app.settings.auto_height {
+                    String::from(app.tr(Str::Auto))
+                } else {
+                    format!("{}h", bar_h)
+                };
+                if app.settings.private_mode {
+                    write!(tv, "{} ", app.tr(Str::PrivateTag)).ok();
+                }
+                write!(
+                    tv,
+                    "{} {}w {} {}  {}",
+                    if upc.is_some() { BarcodeFormat::UpcA.short() } else { barcode.format.short() },
+                    bar_w,
+                    height,
+                    app.tr(scan_hint(layout, bar_w, total_w)),
+                    // With a prefix or suffix, show what was really encoded
+                    if app.settings.prefix.is_empty() && app.settings.suffix.is_empty() {
+                        app.tr(Str::DisplayHint)
+                    } else {
+                        app.barcode_text.as_str()
+                    },
+                ).ok();
+            }
             gam.post_textview(&mut tv).ok();
         }
     }
 }
 
-fn draw_save_prompt(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
-    draw_display(app, gam, canvas);
+/// Pixels between the frame and the symbol it outlines.
+const FRAME_GAP: isize = 2;
+
+/// Outline the whole symbol, quiet zones included, `FRAME_GAP` outside it so
+/// the line never lands in a quiet zone. Sides off the content area are
+/// clipped.
+fn draw_frame(layout: Layout, gam: &Gam, canvas: graphics_server::Gid, x_start: isize, y_offset: isize, total_w: isize, bar_h: isize) {
+    let frame = graphics_server::Rectangle::new_coords_with_style(
+        (x_start - FRAME_GAP - 1).max(0),
+        (y_offset - FRAME_GAP - 1).max(CONTENT_TOP),
+        (x_start + total_w + FRAME_GAP).min(layout.width - 1),
+        y_offset + bar_h + FRAME_GAP,
+        graphics_server::DrawStyle {
+            fill_color: None,
+            stroke_color: Some(graphics_server::PixelColor::Dark),
+            stroke_width: 1,
+        },
+    );
+    gam.draw_rectangle(canvas, frame).ok();
+}
+
+/// Glyph style and its height in pixels for the Display's text line.
+fn text_glyph(size: TextSize) -> (GlyphStyle, isize) {
+    match size {
+        TextSize::Small => (GlyphStyle::Small, 12),
+        TextSize::Normal => (GlyphStyle::Monospace, REGULAR_HEIGHT),
+        TextSize::Large => (GlyphStyle::Large, 24),
+    }
+}
+
+/// Height the Display needs under the bars: gap, text line, gap, status line.
+fn display_below(text_line: isize) -> isize {
+    8 + text_line + 4 + LINE_HEIGHT
+}
+
+/// Where the Display puts the bars, the text line and the status line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DisplayRows {
+    bar_h: isize,
+    y_offset: isize,
+    text_y: isize,
+    status_y: isize,
+}
+
+/// Lay out the Display for bars `bar_height` tall, or as tall as fits with
+/// `full_height`, over a text line `text_line` high. Tall bars are cut
+/// short to keep the text and status lines above the footer. A canvas too
+/// short for even that keeps the bars 1px tall and starting below the header.
+fn display_rows(layout: Layout, bar_height: isize, full_height: bool, text_line: isize) -> DisplayRows {
+    let room = (layout.content_height() - display_below(text_line)).max(1);
+    let bar_h = if full_height { room } else { bar_height.clamp(1, room) };
+    let y_offset = ((layout.content_height() - bar_h - 40 - (text_line - LINE_HEIGHT)).max(0) / 2 + CONTENT_TOP)
+        .min(layout.content_bottom() - display_below(text_line) - bar_h)
+        .max(CONTENT_TOP);
+    let text_y = y_offset + bar_h + 8;
+    DisplayRows { bar_h, y_offset, text_y, status_y: text_y + text_line + 4 }
+}
+
+/// `bar_h` is the drawn height, which `draw_display` may clamp below the setting.
+fn layout_bars(layout: Layout, barcode: &Barcode, app: &BarcodeApp, bar_h: isize, x_start: isize, y_offset: isize) -> RenderCache {
+    let bar_w = app.settings.bar_width as isize;
+    RenderCache {
+        text: barcode.text.clone(),
+        format: barcode.format,
+        bar_width: app.settings.bar_width,
+        bar_height: app.settings.bar_height,
+        full_height: app.full_height(),
+        mirror: app.settings.mirror,
+        center_bars: app.settings.center_bars,
+        text_size: app.settings.text_size,
+        screen: (layout.width, layout.height),
+        rects: bar_rects(layout, barcode, bar_w, bar_h, x_start, y_offset, app.settings.mirror),
+    }
+}
+
+/// Compute one rectangle per run of adjacent dark modules, clipped to the
+/// screen width. Covers exactly the same pixels as one rectangle per module.
+/// `mirror` lays the modules out right-to-left without touching `modules`.
+fn bar_rects(
+    layout: Layout,
+    barcode: &Barcode,
+    bar_w: isize,
+    bar_h: isize,
+    x_start: isize,
+    y_offset: isize,
+    mirror: bool,
+) -> Vec<(isize, isize, isize, isize)> {
+    let mut rects = Vec::new();
+    let mut run_start: Option<isize> = None;
+    let mut run_end = 0;
+    let count = barcode.modules.len();
+    for i in 0..count {
+        let dark = barcode.modules.get(if mirror { count - 1 - i } else { i });
+        let x = x_start + (i as isize) * bar_w;
+        if dark {
+            if x + bar_w > layout.width {
+                break; // clip to screen
+            }
+            if run_start.is_none() {
+                run_start = Some(x);
+            }
+            run_end = x + bar_w;
+        } else if let Some(x0) = run_start.take() {
+            rects.push((x0, y_offset, run_end, y_offset + bar_h));
+        }
+    }
+    if let Some(x0) = run_start {
+        rects.push((x0, y_offset, run_end, y_offset + bar_h));
+    }
+    rects
+}
+
+/// Print layout: up to `tile_count` copies at 1px per module in a grid,
+/// without the human-readable text. Copies that don't fit are skipped.
+fn draw_tiles(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid, barcode: &Barcode) {
+    let tile_w = barcode.modules.len() as isize;
+    let cols = ((layout.width - TILE_GAP) / (tile_w + TILE_GAP)).max(0);
+    let rows = (layout.content_height() - LINE_HEIGHT - TILE_GAP) / (TILE_BAR_HEIGHT + TILE_GAP);
+    let wanted = app.settings.tile_count as isize;
+    let shown = wanted.min(cols * rows);
+
+    if shown > 0 {
+        let used_cols = cols.min(shown);
+        let used_rows = (shown + used_cols - 1) / used_cols;
+        let grid_w = used_cols * (tile_w + TILE_GAP) - TILE_GAP;
+        let grid_h = used_rows * (TILE_BAR_HEIGHT + TILE_GAP) - TILE_GAP;
+        let x0 = (layout.width - grid_w) / 2;
+        let y0 = CONTENT_TOP + (layout.content_height() - LINE_HEIGHT - grid_h) / 2;
 
-    let dialog_y = 200isize;
+        for n in 0..shown {
+            let x = x0 + (n % used_cols) * (tile_w + TILE_GAP);
+            let y = y0 + (n / used_cols) * (TILE_BAR_HEIGHT + TILE_GAP);
+            for (rx0, ry0, rx1, ry1) in bar_rects(layout, barcode, 1, TILE_BAR_HEIGHT, x, y, app.settings.mirror) {
+                let rect = graphics_server::Rectangle::new_coords_with_style(
+                    rx0, ry0, rx1, ry1,
+                    graphics_server::DrawStyle::new(
+                        graphics_server::PixelColor::Dark,
+                        graphics_server::PixelColor::Dark,
+                        0,
+                    ),
+                );
+                gam.draw_rectangle(canvas, rect).ok();
+            }
+        }
+    }
+
+    let status_y = layout.content_bottom() - LINE_HEIGHT;
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+            4, status_y, layout.width - 4, status_y + LINE_HEIGHT,
+        )),
+    );
+    tv.style = GlyphStyle::Small;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    write!(tv, "{} {}/{}  {}", app.tr(Str::Tiles), shown, wanted, app.tr(Str::TileHint)).ok();
+    gam.post_textview(&mut tv).ok();
+}
+
+/// Two codes one above the other, each centred in half the content height
+/// with its own human-readable line. Bars get shorter to fit if needed.
+fn draw_stacked(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid, first: &Barcode, second: &Barcode) {
+    let bar_w = app.settings.bar_width as isize;
+    let half = (layout.content_height() - LINE_HEIGHT - 4) / 2;
+    let bar_h = (app.settings.bar_height as isize).min(half - LINE_HEIGHT - 16);
+    let mut widest = 0;
+
+    for (n, barcode) in [first, second].iter().enumerate() {
+        let total_w = barcode.modules.len() as isize * bar_w;
+        widest = widest.max(total_w);
+        let x_start = if total_w > layout.width - 8 { 4 } else { (layout.width - total_w) / 2 };
+        let y = CONTENT_TOP + n as isize * half + (half - bar_h - LINE_HEIGHT - 8) / 2;
+        for (x0, y0, x1, y1) in bar_rects(layout, barcode, bar_w, bar_h, x_start, y, app.settings.mirror) {
+            let rect = graphics_server::Rectangle::new_coords_with_style(
+                x0, y0, x1, y1,
+                graphics_server::DrawStyle::new(
+                    graphics_server::PixelColor::Dark,
+                    graphics_server::PixelColor::Dark,
+                    0,
+                ),
+            );
+            gam.draw_rectangle(canvas, rect).ok();
+        }
+
+        let text_y = y + bar_h + 4;
+        let mut tv = TextView::new(
+            canvas,
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+                8, text_y, layout.width - 8, text_y + LINE_HEIGHT,
+            )),
+        );
+        tv.style = GlyphStyle::Monospace;
+        tv.draw_border = false;
+        tv.margin = Point::new(0, 0);
+        let printable: String = barcode.text.chars().filter(|c| !c.is_control()).collect();
+        write!(tv, "{}", printable).ok();
+        gam.post_textview(&mut tv).ok();
+    }
+
+    let status_y = layout.content_bottom() - LINE_HEIGHT;
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+            4, status_y, layout.width - 4, status_y + LINE_HEIGHT,
+        )),
+    );
+    tv.style = GlyphStyle::Small;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    if let Some(ref msg) = app.status_message {
+        tv.style = GlyphStyle::Bold;
+        write!(tv, "{}", msg).ok();
+    } else {
+        write!(
+            tv,
+            "{}+{} {}w {}h {}  {}",
+            first.format.short(),
+            second.format.short(),
+            bar_w,
+            bar_h,
+            app.tr(scan_hint(layout, bar_w, widest)),
+            app.tr(Str::DisplayHint),
+        ).ok();
+    }
+    gam.post_textview(&mut tv).ok();
+}
+
+/// Modules between ruler ticks; every fifth tick is drawn taller.
+const RULER_STEP: usize = 10;
+
+/// Debug overlay: 1px ticks above the bars at every `RULER_STEP`th module
+/// boundary, for checking alignment and counting modules.
+fn draw_ruler(layout: Layout, gam: &Gam, canvas: graphics_server::Gid, modules: usize, bar_w: isize, x_start: isize, y_offset: isize) {
+    for n in (0..=modules).step_by(RULER_STEP) {
+        let x = x_start + n as isize * bar_w;
+        if x >= layout.width {
+            break;
+        }
+        let len = if n % (RULER_STEP * 5) == 0 { 8 } else { 4 };
+        let tick = graphics_server::Rectangle::new_coords_with_style(
+            x, y_offset - 2 - len, x + 1, y_offset - 2,
+            graphics_server::DrawStyle::new(
+                graphics_server::PixelColor::Dark,
+                graphics_server::PixelColor::Dark,
+                0,
+            ),
+        );
+        gam.draw_rectangle(canvas, tick).ok();
+    }
+}
+
+/// Characters of run-length dump that fit the content area in Small.
+const MAX_BITS_DUMP: usize = 1200;
+
+/// Debug view: module count and the module stream as runs, `D` dark and
+/// `L` light, e.g. `L10 D2 L1 D1`. Long codes are truncated.
+fn draw_module_bits(layout: Layout, gam: &Gam, canvas: graphics_server::Gid, barcode: &Barcode) {
+    draw_header(layout, gam, canvas, "Module Bits");
+
+    let runs = barcode.modules.runs();
+    let dark = barcode.modules.iter().filter(|&d| d).count();
+    let mut dump = format!(
+        "{} {}: {} modules, {} dark, {} runs\n\n",
+        barcode.format.short(),
+        barcode.text,
+        barcode.modules.len(),
+        dark,
+        runs.len(),
+    );
+    for &(is_dark, len) in runs.iter() {
+        if dump.len() > MAX_BITS_DUMP {
+            dump.push_str("...");
+            break;
+        }
+        dump.push_str(&format!("{}{} ", if is_dark { 'D' } else { 'L' }, len));
+    }
+
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+            4, CONTENT_TOP + 4, layout.width - 4, layout.content_bottom() - 4,
+        )),
+    );
+    tv.style = GlyphStyle::Small;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    write!(tv, "{}", dump).ok();
+    gam.post_textview(&mut tv).ok();
+}
+
+fn draw_save_prompt(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_display(layout, app, gam, canvas);
+    draw_dialog(layout, gam, canvas, app.tr(Str::SavePrompt));
+}
+
+fn draw_confirm_duplicate(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_display(layout, app, gam, canvas);
+    let msg = format!("{} {}\n\n{}", app.tr(Str::AlreadySavedAs), app.duplicate_name, app.tr(Str::SaveAnyway));
+    draw_dialog(layout, gam, canvas, &msg);
+}
+
+fn draw_confirm_discard(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_input(layout, app, gam, canvas);
+    draw_dialog(layout, gam, canvas, app.tr(Str::DiscardPrompt));
+}
+
+/// Yes/no question in a box over the current screen.
+fn draw_dialog(layout: Layout, gam: &Gam, canvas: graphics_server::Gid, msg: &str) {
     let dialog_h = 80isize;
+    let dialog_y = CONTENT_TOP + (layout.content_height() - dialog_h).max(0) / 2;
     let bg = graphics_server::Rectangle::new_coords_with_style(
-        30, dialog_y, SCREEN_WIDTH - 30, dialog_y + dialog_h,
+        30, dialog_y, layout.width - 30, dialog_y + dialog_h,
         graphics_server::DrawStyle {
             fill_color: Some(graphics_server::PixelColor::Light),
             stroke_color: Some(graphics_server::PixelColor::Dark),
@@ -321,33 +1014,155 @@ fn draw_save_prompt(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
     let mut tv = TextView::new(
         canvas,
         TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-            44, dialog_y + 12, SCREEN_WIDTH - 44, dialog_y + dialog_h - 12,
+            44, dialog_y + 12, layout.width - 44, dialog_y + dialog_h - 12,
         )),
     );
     tv.style = GlyphStyle::Regular;
     tv.draw_border = false;
     tv.margin = Point::new(0, 0);
-    write!(tv, "Save this barcode?\n\nY: Yes  N: No").ok();
+    write!(tv, "{}", msg).ok();
     gam.post_textview(&mut tv).ok();
 }
 
-fn draw_save_name(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
-    draw_header(gam, canvas, "Save Barcode");
+fn draw_save_name(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_text_entry(
+        layout, app, gam, canvas,
+        [Str::SaveTitle, Str::EnterName, Str::TypeName, Str::SaveNameHint],
+        &app.save_name,
+    );
+}
+
+fn draw_category_entry(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_text_entry(
+        layout, app, gam, canvas,
+        [Str::CategoryTitle, Str::EnterCategory, Str::NoCategory, Str::CategoryHint],
+        &app.category_input,
+    );
+}
+
+fn draw_compose(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    let (title, first, second) = if app.compose_price {
+        (Str::PriceTitle, Str::EnterItemNumber, Str::EnterPrice)
+    } else {
+        (Str::ComposeTitle, Str::EnterPrefix, Str::EnterItemRef)
+    };
+    // The first field stays in view, spaced off, while the second is typed
+    let (prompt, value) = if app.compose_on_item {
+        (second, format!("{} {}", app.compose_prefix, app.compose_item))
+    } else {
+        (first, app.compose_prefix.clone())
+    };
+    draw_text_entry(layout, app, gam, canvas, [title, prompt, Str::TypeDigits, Str::ComposeHint], &value);
+}
+
+/// Height of one Keypad key.
+const KEYPAD_KEY_HEIGHT: isize = 72;
+
+/// Input text over a phone-style digit grid, the selected key inverted.
+fn draw_keypad(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_header(layout, gam, canvas, app.tr(Str::KeypadTitle));
+
+    let box_y = CONTENT_TOP + 12;
+    let border = graphics_server::Rectangle::new_coords_with_style(
+        16, box_y, layout.width - 16, box_y + LINE_HEIGHT + 16,
+        graphics_server::DrawStyle {
+            fill_color: Some(graphics_server::PixelColor::Light),
+            stroke_color: Some(graphics_server::PixelColor::Dark),
+            stroke_width: 1,
+        },
+    );
+    gam.draw_rectangle(canvas, border).ok();
+    let display = if app.input_text.is_empty() { app.tr(Str::Empty) } else { &app.input_text };
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+            24, box_y + 4, layout.width - 24, box_y + LINE_HEIGHT + 12,
+        )),
+    );
+    tv.style = GlyphStyle::Monospace;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    write!(tv, "{}", display).ok();
+    gam.post_textview(&mut tv).ok();
+
+    let grid_y = box_y + LINE_HEIGHT + 32;
+    let key_w = (layout.width - 32) / KEYPAD_COLUMNS as isize;
+    for (i, key) in KEYPAD_KEYS.iter().enumerate() {
+        let x = 16 + (i % KEYPAD_COLUMNS) as isize * key_w;
+        let y = grid_y + (i / KEYPAD_COLUMNS) as isize * KEYPAD_KEY_HEIGHT;
+        let selected = i == app.keypad_index;
+        let fill = if selected { graphics_server::PixelColor::Dark } else { graphics_server::PixelColor::Light };
+        let cell = graphics_server::Rectangle::new_coords_with_style(
+            x + 2, y + 2, x + key_w - 2, y + KEYPAD_KEY_HEIGHT - 2,
+            graphics_server::DrawStyle {
+                fill_color: Some(fill),
+                stroke_color: Some(graphics_server::PixelColor::Dark),
+                stroke_width: 1,
+            },
+        );
+        gam.draw_rectangle(canvas, cell).ok();
+
+        let mut tv = TextView::new(
+            canvas,
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+                x + 8, y + KEYPAD_KEY_HEIGHT / 2 - 14, x + key_w - 8, y + KEYPAD_KEY_HEIGHT / 2 + 14,
+            )),
+        );
+        tv.style = GlyphStyle::Large;
+        tv.invert = selected;
+        tv.draw_border = false;
+        tv.margin = Point::new(0, 0);
+        match key {
+            KeypadKey::Digit(d) => write!(tv, "{}", d).ok(),
+            KeypadKey::Delete => write!(tv, "{}", app.tr(Str::KeypadDelete)).ok(),
+            KeypadKey::Done => write!(tv, "{}", app.tr(Str::KeypadDone)).ok(),
+        };
+        gam.post_textview(&mut tv).ok();
+    }
+
+    let hint_y = grid_y + 4 * KEYPAD_KEY_HEIGHT + 8;
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, hint_y, layout.width - 16, hint_y + LINE_HEIGHT)),
+    );
+    tv.style = GlyphStyle::Small;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    write!(tv, "{}", app.status_message.as_deref().unwrap_or(app.tr(Str::KeypadHint))).ok();
+    gam.post_textview(&mut tv).ok();
+
+    draw_footer(layout, gam, canvas, &["", "", "", ""]);
+}
+
+fn draw_affix_entry(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    let title = if app.affix_is_suffix { Str::SuffixTitle } else { Str::PrefixTitle };
+    draw_text_entry(
+        layout, app, gam, canvas,
+        [title, Str::EnterAffix, Str::AffixNone, Str::CategoryHint],
+        &app.affix_input,
+    );
+}
+
+/// One-line text entry screen. `labels` are the title, prompt, placeholder
+/// shown while `value` is empty, and key hint.
+fn draw_text_entry(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid, labels: [Str; 4], value: &str) {
+    let [title, prompt, placeholder, hint] = labels;
+    draw_header(layout, gam, canvas, app.tr(title));
 
     let y = CONTENT_TOP + 30;
     let mut tv = TextView::new(
         canvas,
-        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, SCREEN_WIDTH - 16, y + LINE_HEIGHT)),
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, layout.width - 16, y + LINE_HEIGHT)),
     );
     tv.style = GlyphStyle::Regular;
     tv.draw_border = false;
     tv.margin = Point::new(0, 0);
-    write!(tv, "Enter a name:").ok();
+    write!(tv, "{}", app.tr(prompt)).ok();
     gam.post_textview(&mut tv).ok();
 
     let box_y = y + LINE_HEIGHT + 16;
     let border = graphics_server::Rectangle::new_coords_with_style(
-        16, box_y, SCREEN_WIDTH - 16, box_y + LINE_HEIGHT + 16,
+        16, box_y, layout.width - 16, box_y + LINE_HEIGHT + 16,
         graphics_server::DrawStyle {
             fill_color: Some(graphics_server::PixelColor::Light),
             stroke_color: Some(graphics_server::PixelColor::Dark),
@@ -356,11 +1171,11 @@ fn draw_save_name(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
     );
     gam.draw_rectangle(canvas, border).ok();
 
-    let display = if app.save_name.is_empty() { "(type a name)" } else { &app.save_name };
+    let display = if value.is_empty() { app.tr(placeholder) } else { value };
     let mut tv = TextView::new(
         canvas,
         TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-            24, box_y + 4, SCREEN_WIDTH - 24, box_y + LINE_HEIGHT + 12,
+            24, box_y + 4, layout.width - 24, box_y + LINE_HEIGHT + 12,
         )),
     );
     tv.style = GlyphStyle::Monospace;
@@ -372,48 +1187,101 @@ fn draw_save_name(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
     let instr_y = box_y + LINE_HEIGHT + 30;
     let mut tv = TextView::new(
         canvas,
-        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, instr_y, SCREEN_WIDTH - 16, instr_y + LINE_HEIGHT)),
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, instr_y, layout.width - 16, instr_y + LINE_HEIGHT)),
     );
     tv.style = GlyphStyle::Small;
     tv.draw_border = false;
     tv.margin = Point::new(0, 0);
-    write!(tv, "Enter: save | Q: cancel").ok();
+    write!(tv, "{}", app.status_message.as_deref().unwrap_or(app.tr(hint))).ok();
     gam.post_textview(&mut tv).ok();
 
-    draw_footer(gam, canvas, &["", "", "", ""]);
+    draw_footer(layout, gam, canvas, &["", "", "", ""]);
+}
+
+/// Thumbnail for saved code `i`, encoding it the first time it is shown.
+fn thumbnail(app: &BarcodeApp, i: usize) -> Option<[bool; THUMB_WIDTH]> {
+    let code = &app.saved_codes[i];
+    let mut cache = app.thumbnails.borrow_mut();
+    if cache.len() < app.saved_codes.len() {
+        cache.resize(app.saved_codes.len(), None);
+    }
+    if let Some(thumb) = cache[i].as_ref().filter(|t| t.text == code.text && t.format == code.format) {
+        return thumb.columns;
+    }
+    let columns = barcode_encode::encode(&code.text, code.format).ok().map(|b| downsample(&b));
+    cache[i] = Some(Thumbnail { text: code.text.clone(), format: code.format, columns });
+    columns
+}
+
+/// Indices of the first and last dark module: the symbol without its quiet
+/// zones. `None` for an all-light symbol.
+fn dark_span(barcode: &Barcode) -> Option<(usize, usize)> {
+    let modules = &barcode.modules;
+    let first = (0..modules.len()).find(|&i| modules.get(i))?;
+    let last = (0..modules.len()).rfind(|&i| modules.get(i)).unwrap_or(first);
+    Some((first, last))
 }
 
-fn draw_load_list(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
-    draw_header(gam, canvas, "Saved Barcodes");
+/// Squeeze the symbol, without its quiet zones, into `THUMB_WIDTH` columns.
+/// A column is dark when at least half of its modules are.
+fn downsample(barcode: &Barcode) -> [bool; THUMB_WIDTH] {
+    let modules = &barcode.modules;
+    let mut columns = [false; THUMB_WIDTH];
+    let (first, last) = match dark_span(barcode) {
+        Some(span) => span,
+        None => return columns,
+    };
+    let span = last + 1 - first;
+    for (c, col) in columns.iter_mut().enumerate() {
+        let start = first + c * span / THUMB_WIDTH;
+        let end = (first + (c + 1) * span / THUMB_WIDTH).max(start + 1);
+        let dark = (start..end).filter(|&i| modules.get(i)).count();
+        *col = dark * 2 >= end - start;
+    }
+    columns
+}
+
+fn draw_load_list(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    match app.category_filter {
+        CategoryFilter::All => draw_header(layout, gam, canvas, app.tr(Str::SavedTitle)),
+        CategoryFilter::Only(ref cat) => {
+            let name = cat.as_deref().unwrap_or(app.tr(Str::NoCategory));
+            draw_header(layout, gam, canvas, &format!("{}: {}", app.tr(Str::SavedTitle), name));
+        }
+    }
+    let visible = app.visible_codes();
 
     if app.saved_codes.is_empty() {
         let mut tv = TextView::new(
             canvas,
             TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-                16, CONTENT_TOP + 30, SCREEN_WIDTH - 16, CONTENT_TOP + 30 + LINE_HEIGHT * 2,
+                16, CONTENT_TOP + 30, layout.width - 16, CONTENT_TOP + 30 + LINE_HEIGHT * 2,
             )),
         );
         tv.style = GlyphStyle::Regular;
         tv.draw_border = false;
         tv.margin = Point::new(0, 0);
-        write!(tv, "No saved barcodes.\n\nPress Q to go back.").ok();
+        match app.status_message {
+            Some(ref msg) => write!(tv, "{}\n{}", app.tr(Str::NoSavedCodes), msg).ok(),
+            None => write!(tv, "{}", app.tr(Str::NoSavedCodes)).ok(),
+        };
         gam.post_textview(&mut tv).ok();
     } else {
-        let max_visible = ((CONTENT_HEIGHT - 20) / (LINE_HEIGHT + 6)) as usize;
+        let max_visible = ((layout.content_height() - 20) / (LINE_HEIGHT + 6)) as usize;
         let scroll_offset = if app.load_index >= max_visible {
             app.load_index - max_visible + 1
         } else {
             0
         };
 
-        for (vi, i) in (scroll_offset..app.saved_codes.len()).take(max_visible).enumerate() {
+        for (vi, (pos, &i)) in visible.iter().enumerate().skip(scroll_offset).take(max_visible).enumerate() {
             let code = &app.saved_codes[i];
             let y = CONTENT_TOP + 12 + (vi as isize) * (LINE_HEIGHT + 6);
-            let selected = i == app.load_index;
+            let selected = pos == app.load_index;
 
             if selected {
                 let hl = graphics_server::Rectangle::new_coords_with_style(
-                    4, y - 2, SCREEN_WIDTH - 4, y + LINE_HEIGHT + 2,
+                    4, y - 2, layout.width - 4, y + LINE_HEIGHT + 2,
                     graphics_server::DrawStyle::new(
                         graphics_server::PixelColor::Dark,
                         graphics_server::PixelColor::Dark,
@@ -423,10 +1291,23 @@ fn draw_load_list(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
                 gam.draw_rectangle(canvas, hl).ok();
             }
 
+            let thumb_x = layout.width - 12 - THUMB_WIDTH as isize;
+            if let Some(columns) = thumbnail(app, i) {
+                let color = if selected { graphics_server::PixelColor::Light } else { graphics_server::PixelColor::Dark };
+                for (c, _) in columns.iter().enumerate().filter(|(_, &dark)| dark) {
+                    let x = thumb_x + c as isize;
+                    let bar = graphics_server::Rectangle::new_coords_with_style(
+                        x, y + 2, x + 1, y + LINE_HEIGHT - 2,
+                        graphics_server::DrawStyle::new(color, color, 0),
+                    );
+                    gam.draw_rectangle(canvas, bar).ok();
+                }
+            }
+
             let mut tv = TextView::new(
                 canvas,
                 TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
-                    12, y, SCREEN_WIDTH - 12, y + LINE_HEIGHT,
+                    12, y, thumb_x - 6, y + LINE_HEIGHT,
                 )),
             );
             tv.style = GlyphStyle::Regular;
@@ -444,130 +1325,297 @@ fn draw_load_list(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
             write!(tv, "{} [{}] {}", code.name, code.format.short(), preview).ok();
             gam.post_textview(&mut tv).ok();
         }
+
+        let hint_y = layout.content_bottom() - LINE_HEIGHT - 4;
+        let mut tv = TextView::new(
+            canvas,
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(8, hint_y, layout.width - 8, hint_y + LINE_HEIGHT)),
+        );
+        tv.style = GlyphStyle::Small;
+        tv.draw_border = false;
+        tv.margin = Point::new(0, 0);
+        match app.status_message {
+            Some(ref msg) => write!(tv, "{}", msg).ok(),
+            None => write!(tv, "{}", app.tr(Str::SavedListHint)).ok(),
+        };
+        gam.post_textview(&mut tv).ok();
     }
 
-    draw_footer(gam, canvas, &["", "", "", ""]);
+    draw_footer(layout, gam, canvas, &["", "", "", ""]);
 }
 
-fn draw_settings(app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
-    draw_header(gam, canvas, "Settings");
+fn draw_settings(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_header(layout, gam, canvas, app.tr(Str::MenuSettings));
 
-    let items: [(&str, &str); 4] = [
-        ("Format", app.settings.format.label()),
-        ("Auto-Detect", if app.settings.auto_format { "On" } else { "Off" }),
-        ("Bar Width", match app.settings.bar_width {
-            1 => "1px", 2 => "2px", 3 => "3px", 4 => "4px", _ => "2px",
-        }),
-        ("Bar Height", match app.settings.bar_height {
-            80 => "80px", 100 => "100px", 120 => "120px", 140 => "140px",
-            160 => "160px", 180 => "180px", 200 => "200px", 220 => "220px",
-            240 => "240px", 260 => "260px", 280 => "280px", 300 => "300px",
-            _ => "200px",
-        }),
-    ];
+    let count = SettingItem::all().len();
+    let scroll = settings_scroll(layout, app.settings_index);
+    let end = count.min(scroll + layout.settings_rows());
+    for i in scroll..end {
+        draw_settings_row(layout, app, gam, canvas, i);
+    }
 
-    for (i, (label, value)) in items.iter().enumerate() {
-        let y = CONTENT_TOP + 20 + (i as isize) * (LINE_HEIGHT + 12);
-        let selected = i == app.settings_index;
+    let y = settings_row_y(layout, app, end) + 16;
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, layout.width - 16, y + SETTINGS_HINT_HEIGHT - 16)),
+    );
+    tv.style = GlyphStyle::Small;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    write!(tv, "{}", app.tr(Str::SettingsHint)).ok();
+    gam.post_textview(&mut tv).ok();
 
-        if selected {
-            let hl = graphics_server::Rectangle::new_coords_with_style(
-                8, y - 4, SCREEN_WIDTH - 8, y + LINE_HEIGHT + 4,
-                graphics_server::DrawStyle::new(
-                    graphics_server::PixelColor::Dark,
-                    graphics_server::PixelColor::Dark,
-                    0,
-                ),
-            );
-            gam.draw_rectangle(canvas, hl).ok();
-        }
+    draw_footer(layout, gam, canvas, &["", "", "", ""]);
+}
+
+/// Settings rows start this far below the header, one every
+/// `SETTINGS_ROW_HEIGHT`.
+const SETTINGS_TOP: isize = 20;
+const SETTINGS_ROW_HEIGHT: isize = LINE_HEIGHT + 12;
+/// Gap and two lines for the key hint under the last row.
+const SETTINGS_HINT_HEIGHT: isize = 16 + LINE_HEIGHT * 2;
+
+/// First settings row shown when `index` is selected.
+fn settings_scroll(layout: Layout, index: usize) -> usize {
+    index.saturating_sub(layout.settings_rows() - 1)
+}
+
+fn settings_row_y(layout: Layout, app: &BarcodeApp, i: usize) -> isize {
+    let row = i - settings_scroll(layout, app.settings_index);
+    CONTENT_TOP + SETTINGS_TOP + (row as isize) * SETTINGS_ROW_HEIGHT
+}
 
+fn setting_value(app: &BarcodeApp, item: SettingItem) -> String {
+    match item {
+        SettingItem::Format => String::from(app.settings.format.label()),
+        SettingItem::AutoDetect => String::from(on_off(app, app.settings.auto_format)),
+        SettingItem::BarWidth => format!("{}px", app.settings.bar_width),
+        SettingItem::BarHeight => format!("{}px", app.settings.bar_height),
+        SettingItem::AutoHeight => String::from(on_off(app, app.settings.auto_height)),
+        SettingItem::TextSize => String::from(app.tr(app.settings.text_size.label())),
+        SettingItem::TrimInput => String::from(on_off(app, app.settings.trim_input)),
+        SettingItem::StripNonDigits => String::from(on_off(app, app.settings.strip_nondigits)),
+        SettingItem::MaxBrightness => String::from(on_off(app, app.settings.max_brightness_on_display)),
+        SettingItem::ScanTestTime => format!("{}s", app.settings.scan_test_secs),
+        SettingItem::Code39Case => String::from(on_off(app, app.settings.code39_preserve_case)),
+        SettingItem::Std2of5Check => String::from(on_off(app, app.settings.std2of5_check)),
+        SettingItem::ShowCheckChar => String::from(on_off(app, app.settings.show_check_char)),
+        SettingItem::EanZeroPad => String::from(on_off(app, app.settings.ean_zero_pad)),
+        SettingItem::UpcText => String::from(on_off(app, app.settings.upc_text)),
+        SettingItem::WideRatio => format!("{}:1", app.settings.wide_ratio),
+        SettingItem::Code39Gap => format!("{}x", app.settings.code39_gap),
+        SettingItem::AppendEnter => String::from(on_off(app, app.settings.append_enter)),
+        SettingItem::Code128ForceB => String::from(on_off(app, app.settings.code128_force_b)),
+        SettingItem::Code128Latin1 => String::from(on_off(app, app.settings.code128_latin1)),
+        SettingItem::KeyRepeat => String::from(on_off(app, app.settings.key_repeat)),
+        SettingItem::Prefix => affix_value(app, &app.settings.prefix),
+        SettingItem::Suffix => affix_value(app, &app.settings.suffix),
+        SettingItem::CheckDigitMode => String::from(app.tr(match app.settings.check_digit_mode {
+            CheckMode::Recompute => Str::CheckRecompute,
+            CheckMode::Require => Str::CheckRequire,
+            CheckMode::Append => Str::CheckAppend,
+        })),
+        SettingItem::ResumeLast => String::from(on_off(app, app.settings.resume_last)),
+        SettingItem::AutoSave => String::from(on_off(app, app.settings.autosave)),
+        SettingItem::PrivateMode => String::from(on_off(app, app.settings.private_mode)),
+        SettingItem::StartScreen => String::from(app.tr(app.settings.start_screen.label())),
+        SettingItem::Mirror => String::from(on_off(app, app.settings.mirror)),
+        SettingItem::CenterBars => String::from(on_off(app, app.settings.center_bars)),
+        SettingItem::DrawFrame => String::from(on_off(app, app.settings.draw_frame)),
+        SettingItem::TileCount => format!("{}", app.settings.tile_count),
+        SettingItem::Language => String::from(app.settings.language.label()),
+    }
+}
+
+fn on_off(app: &BarcodeApp, on: bool) -> &'static str {
+    app.tr(if on { Str::On } else { Str::Off })
+}
+
+fn affix_value(app: &BarcodeApp, affix: &str) -> String {
+    String::from(if affix.is_empty() { app.tr(Str::AffixNone) } else { affix })
+}
+
+fn draw_settings_row(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid, i: usize) {
+    let item = match SettingItem::all().get(i) {
+        Some(&item) => item,
+        None => return,
+    };
+    let value = setting_value(app, item);
+    let y = settings_row_y(layout, app, i);
+    let selected = i == app.settings_index;
+
+    if selected {
+        let hl = graphics_server::Rectangle::new_coords_with_style(
+            8, y - 4, layout.width - 8, y + LINE_HEIGHT + 4,
+            graphics_server::DrawStyle::new(
+                graphics_server::PixelColor::Dark,
+                graphics_server::PixelColor::Dark,
+                0,
+            ),
+        );
+        gam.draw_rectangle(canvas, hl).ok();
+    }
+
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, 180, y + LINE_HEIGHT)),
+    );
+    tv.style = GlyphStyle::Regular;
+    tv.invert = selected;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    write!(tv, "{}", app.tr(item.label())).ok();
+    gam.post_textview(&mut tv).ok();
+
+    let mut tv = TextView::new(
+        canvas,
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(190, y, layout.width - 16, y + LINE_HEIGHT)),
+    );
+    tv.style = GlyphStyle::Bold;
+    tv.invert = selected;
+    tv.draw_border = false;
+    tv.margin = Point::new(0, 0);
+    write!(tv, "<  {}  >", value).ok();
+    gam.post_textview(&mut tv).ok();
+}
+
+fn draw_format_info(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    let format = app.info_format;
+    draw_header(layout, gam, canvas, format.label());
+
+    let c = format.constraints();
+    let length = match c.max_len {
+        Some(max) if max == c.min_len => format!("{}", max),
+        Some(max) => format!("{}-{}", c.min_len, max),
+        None => format!("{}+", c.min_len),
+    };
+    let rows = [
+        (Str::InfoChars, String::from(c.chars)),
+        (Str::InfoLength, length),
+        (Str::InfoCheck, String::from(c.check)),
+        (Str::InfoExample, String::from(c.example)),
+    ];
+    let mut y = CONTENT_TOP + 8;
+    for (label, value) in rows.iter() {
         let mut tv = TextView::new(
             canvas,
-            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, 180, y + LINE_HEIGHT)),
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(8, y, layout.width - 8, y + REGULAR_HEIGHT)),
         );
-        tv.style = GlyphStyle::Regular;
-        tv.invert = selected;
+        tv.style = GlyphStyle::Bold;
         tv.draw_border = false;
         tv.margin = Point::new(0, 0);
-        write!(tv, "{}", label).ok();
+        write!(tv, "{}", app.tr(*label)).ok();
         gam.post_textview(&mut tv).ok();
 
         let mut tv = TextView::new(
             canvas,
-            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(190, y, SCREEN_WIDTH - 16, y + LINE_HEIGHT)),
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(
+                16, y + REGULAR_HEIGHT + 2, layout.width - 8, y + REGULAR_HEIGHT * 3,
+            )),
         );
-        tv.style = GlyphStyle::Bold;
-        tv.invert = selected;
+        tv.style = GlyphStyle::Regular;
         tv.draw_border = false;
         tv.margin = Point::new(0, 0);
-        write!(tv, "<  {}  >", value).ok();
+        write!(tv, "{}", value).ok();
         gam.post_textview(&mut tv).ok();
+        y += REGULAR_HEIGHT * 3 + 8;
     }
 
-    let y = CONTENT_TOP + 20 + (items.len() as isize) * (LINE_HEIGHT + 12) + 16;
+    let hint_y = layout.content_bottom() - LINE_HEIGHT - 4;
     let mut tv = TextView::new(
         canvas,
-        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(16, y, SCREEN_WIDTH - 16, y + LINE_HEIGHT * 2)),
+        TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(8, hint_y, layout.width - 8, hint_y + LINE_HEIGHT)),
     );
     tv.style = GlyphStyle::Small;
     tv.draw_border = false;
     tv.margin = Point::new(0, 0);
-    write!(tv, "Up/Down: select | Left/Right: change\nQ: back").ok();
+    write!(tv, "{}", app.tr(Str::InfoHint)).ok();
     gam.post_textview(&mut tv).ok();
 
-    draw_footer(gam, canvas, &["", "", "", ""]);
-}
-
-fn draw_help(_app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
-    draw_header(gam, canvas, "Help");
-
-    let help_text = [
-        "Barcode Generator v0.1",
-        "",
-        "FORMATS",
-        "  Code 128: Full ASCII",
-        "  Code 39: A-Z, 0-9, symbols",
-        "  EAN-13: 12-13 digit products",
-        "  UPC-A: 11-12 digit products",
-        "",
-        "INPUT",
-        "  Type text, Enter to generate",
-        "  F1: Code 128  F2: Code 39",
-        "  F3: EAN-13    F4: UPC-A",
-        "",
-        "DISPLAY",
-        "  S: Save  N: New  Q: Back",
-        "  Up/Down: Bar height",
-        "  Left/Right: Bar width",
-        "",
-        "SAVED CODES",
-        "  Enter: Load  D: Delete",
-        "",
-        "Auto-detect picks format",
-        "from your input text.",
-    ];
+    draw_footer(layout, gam, canvas, &["", "", "", ""]);
+}
 
-    for (i, line) in help_text.iter().enumerate() {
+fn draw_help(layout: Layout, app: &BarcodeApp, gam: &Gam, canvas: graphics_server::Gid) {
+    draw_header(layout, gam, canvas, app.tr(Str::HelpTitle));
+
+    let lines = strings::help_lines(app.settings.language).iter().skip(app.help_scroll);
+    for (i, &(heading, line)) in lines.enumerate() {
         let y = CONTENT_TOP + 4 + (i as isize) * (REGULAR_HEIGHT + 2);
-        if y + REGULAR_HEIGHT > CONTENT_BOTTOM { break; }
+        if y + REGULAR_HEIGHT > layout.content_bottom() { break; }
 
         let mut tv = TextView::new(
             canvas,
-            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(8, y, SCREEN_WIDTH - 8, y + REGULAR_HEIGHT)),
+            TextBounds::BoundingBox(graphics_server::Rectangle::new_coords(8, y, layout.width - 8, y + REGULAR_HEIGHT)),
         );
-        tv.style = if line.starts_with("Barcode") || line.starts_with("FORMATS")
-            || line.starts_with("INPUT") || line.starts_with("DISPLAY")
-            || line.starts_with("SAVED") || line.starts_with("Auto") {
-            GlyphStyle::Bold
-        } else {
-            GlyphStyle::Small
-        };
+        tv.style = if heading { GlyphStyle::Bold } else { GlyphStyle::Small };
         tv.draw_border = false;
         tv.margin = Point::new(0, 0);
         write!(tv, "{}", line).ok();
         gam.post_textview(&mut tv).ok();
     }
 
-    draw_footer(gam, canvas, &["", "", "", ""]);
+    draw_footer(layout, gam, canvas, &["", "", "", ""]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRECURSOR: Layout = Layout { width: 336, height: 536 };
+
+    #[test]
+    fn display_rows_fit_at_the_largest_bar_height() {
+        for size in [TextSize::Small, TextSize::Normal, TextSize::Large] {
+            let text_line = text_glyph(size).1 + LINE_GAP;
+            for (bar_height, full_height) in [(80, false), (300, false), (isize::MAX, false), (80, true)] {
+                let rows = display_rows(PRECURSOR, bar_height, full_height, text_line);
+                assert!(rows.y_offset >= CONTENT_TOP, "{:?}", rows);
+                assert!(rows.text_y >= rows.y_offset + rows.bar_h, "{:?}", rows);
+                assert!(rows.text_y + text_line < PRECURSOR.content_bottom(), "{:?}", rows);
+                assert!(rows.status_y + LINE_HEIGHT <= PRECURSOR.content_bottom(), "{:?}", rows);
+            }
+            // The largest setting is cut short only to make room for the text
+            let rows = display_rows(PRECURSOR, 300, false, text_line);
+            assert_eq!(rows.bar_h, 300.min(PRECURSOR.content_height() - display_below(text_line)));
+        }
+        assert_eq!(display_rows(PRECURSOR, 120, false, LINE_HEIGHT).bar_h, 120);
+    }
+
+    #[test]
+    fn display_rows_on_a_short_canvas() {
+        let layout = Layout { width: 336, height: 120 };
+        let rows = display_rows(layout, 300, false, LINE_HEIGHT);
+        assert_eq!(rows.bar_h, 1);
+        assert_eq!(rows.y_offset, CONTENT_TOP);
+    }
+
+    #[test]
+    fn settings_rows_fit_the_canvas() {
+        assert_eq!(PRECURSOR.settings_rows(), 12);
+        let rows = PRECURSOR.settings_rows() as isize;
+        assert!(CONTENT_TOP + SETTINGS_TOP + rows * SETTINGS_ROW_HEIGHT + SETTINGS_HINT_HEIGHT <= PRECURSOR.content_bottom());
+        assert_eq!(Layout { width: 336, height: 100 }.settings_rows(), 1);
+    }
+
+    #[test]
+    fn thumbnails_follow_the_saved_list() {
+        let mut app = BarcodeApp::new();
+        let code = |name: &str, text: &str| crate::app::SavedBarcode {
+            name: String::from(name),
+            text: String::from(text),
+            format: BarcodeFormat::Code128,
+            category: None,
+            created: 0,
+        };
+        app.saved_codes = vec![code("A", "AAA"), code("B", "BBB")];
+        assert!(thumbnail(&app, 1).is_some());
+        assert_eq!(app.thumbnails.borrow().len(), 2);
+
+        app.state = AppState::LoadList;
+        app.handle_key('d');
+        assert!(app.thumbnails.borrow().is_empty());
+        let b = barcode_encode::encode("BBB", BarcodeFormat::Code128).unwrap();
+        assert_eq!(thumbnail(&app, 0), Some(downsample(&b)));
+        assert_eq!(app.thumbnails.borrow().len(), 1);
+    }
 }